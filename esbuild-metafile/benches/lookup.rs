@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use divan::Bencher;
+use divan::black_box;
+use esbuild_metafile::esbuild_metafile::EsbuildMetafile;
+use esbuild_metafile::input_lookup::InputLookup;
+use esbuild_metafile::output_lookup::OutputLookup;
+use esbuild_metafile::raw_esbuild_metafile::RawEsbuildMetafile;
+use serde_json::Value;
+use serde_json::json;
+
+const ENTRY_COUNT: usize = 2_000;
+const CHUNK_COUNT: usize = 6_000;
+const CHUNKS_PER_ENTRY: usize = 8;
+
+/// 10k outputs: entries with a css bundle each, importing a handful of shared chunks.
+/// Chunks form short chains and import a font.
+fn synthetic_metafile() -> RawEsbuildMetafile {
+    let mut outputs = serde_json::Map::new();
+
+    for chunk in 0..CHUNK_COUNT {
+        let mut imports = vec![json!({
+            "path": format!("https://fonts/font-{}.woff2", chunk % 16),
+            "kind": "url-token",
+            "external": true
+        })];
+
+        if chunk % 8 != 7 {
+            imports.push(json!({
+                "path": format!("static/chunk-{}.js", chunk + 1),
+                "kind": "import-statement"
+            }));
+        }
+
+        outputs.insert(
+            format!("static/chunk-{chunk}.js"),
+            json!({
+                "imports": imports,
+                "inputs": {format!("resources/ts/chunk_{chunk}.ts"): {"bytesInOutput": 100}},
+                "bytes": 100
+            }),
+        );
+    }
+
+    for entry in 0..ENTRY_COUNT {
+        let imports: Vec<Value> = (0..CHUNKS_PER_ENTRY)
+            .map(|offset| {
+                json!({
+                    "path": format!("static/chunk-{}.js", (entry * 97 + offset * 13) % CHUNK_COUNT),
+                    "kind": "import-statement"
+                })
+            })
+            .collect();
+
+        outputs.insert(
+            format!("static/controller_{entry}.css"),
+            json!({
+                "imports": [],
+                "inputs": {format!("resources/css/controller_{entry}.css"): {"bytesInOutput": 500}},
+                "bytes": 500
+            }),
+        );
+        outputs.insert(
+            format!("static/controller_{entry}.js"),
+            json!({
+                "imports": imports,
+                "entryPoint": format!("resources/ts/controller_{entry}.tsx"),
+                "cssBundle": format!("static/controller_{entry}.css"),
+                "bytes": 1000
+            }),
+        );
+    }
+
+    serde_json::from_value(json!({"outputs": outputs})).expect("synthetic metafile parses")
+}
+
+static RAW_METAFILE: LazyLock<RawEsbuildMetafile> = LazyLock::new(synthetic_metafile);
+
+static METAFILE: LazyLock<EsbuildMetafile> =
+    LazyLock::new(|| EsbuildMetafile::from(RAW_METAFILE.clone()));
+
+static ENTRY_PATHS: LazyLock<Vec<(String, String)>> = LazyLock::new(|| {
+    (0..ENTRY_COUNT)
+        .map(|entry| {
+            (
+                format!("resources/ts/controller_{entry}.tsx"),
+                format!("static/controller_{entry}.js"),
+            )
+        })
+        .collect()
+});
+
+/// The owned `Vec<String>` lists that lookups used to clone before paths were interned.
+static OWNED_LISTS: LazyLock<HashMap<String, Vec<String>>> = LazyLock::new(|| {
+    let mut lists = HashMap::new();
+
+    for (input_path, output_path) in ENTRY_PATHS.iter() {
+        if let InputLookup::Found(input) = METAFILE.input(input_path) {
+            lists.insert(
+                input_path.clone(),
+                input.outputs.iter().map(|path| path.to_string()).collect(),
+            );
+        }
+
+        if let OutputLookup::Found(output) = METAFILE.output(output_path) {
+            lists.insert(
+                output_path.clone(),
+                output
+                    .preloads
+                    .iter()
+                    .map(|path| path.to_string())
+                    .collect(),
+            );
+        }
+    }
+
+    lists
+});
+
+fn main() {
+    divan::main();
+}
+
+#[divan::bench]
+fn page_render_lookups(bencher: Bencher) {
+    let metafile = &*METAFILE;
+
+    bencher.bench(|| {
+        for (input_path, output_path) in ENTRY_PATHS.iter().take(64) {
+            if let InputLookup::Found(input) = metafile.input(black_box(input_path)) {
+                black_box(input.outputs.len());
+            }
+
+            if let OutputLookup::Found(output) = metafile.output(black_box(output_path)) {
+                black_box(output.preloads.len());
+            }
+        }
+    });
+}
+
+#[divan::bench]
+fn page_render_lookups_cloning_owned_lists(bencher: Bencher) {
+    let lists = &*OWNED_LISTS;
+
+    bencher.bench(|| {
+        for (input_path, output_path) in ENTRY_PATHS.iter().take(64) {
+            black_box(lists.get(black_box(input_path.as_str())).cloned());
+            black_box(lists.get(black_box(output_path.as_str())).cloned());
+        }
+    });
+}
+
+#[divan::bench(sample_count = 10)]
+fn build_index(bencher: Bencher) {
+    bencher
+        .with_inputs(|| RAW_METAFILE.clone())
+        .bench_values(EsbuildMetafile::from);
+}
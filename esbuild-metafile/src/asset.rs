@@ -1,7 +1,14 @@
-use crate::filesystem::get_file_extension;
+use crate::asset_type_registry::AssetTypeRegistry;
+use crate::cross_origin::CrossOrigin;
+use crate::fetch_priority::FetchPriority;
+use crate::html_tag::HtmlTag;
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+use crate::render_context::RenderContext;
 use crate::renders_path::RendersPath;
+use crate::script_type::ScriptType;
+use crate::subresource_integrity::SubresourceIntegrity;
 
-#[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Asset {
     Stylesheet(String),
     Script(String),
@@ -10,23 +17,85 @@ pub enum Asset {
 
 impl Asset {
     pub fn from_path(path: String) -> Self {
-        match get_file_extension(&path) {
-            Some("js") => Asset::Script(path),
-            Some("css") => Asset::Stylesheet(path),
+        Self::from_path_with_registry(path, AssetTypeRegistry::default_registry())
+    }
+
+    pub fn from_path_with_registry(path: String, registry: &AssetTypeRegistry) -> Self {
+        match registry.kind(&path) {
+            PreloadableAssetKind::Module => Asset::Script(path),
+            PreloadableAssetKind::Stylesheet => Asset::Stylesheet(path),
             _ => Asset::Unknown(path),
         }
     }
 
+    pub fn path(&self) -> &str {
+        match self {
+            Asset::Script(path) | Asset::Stylesheet(path) | Asset::Unknown(path) => path,
+        }
+    }
+
     pub fn render<TRendersPath: RendersPath>(&self, renders_path: &TRendersPath) -> String {
+        self.render_with_context(&RenderContext::new(renders_path))
+    }
+
+    /// Adds the `integrity` of the asset, if known, together with the `crossorigin`
+    /// attribute that integrity checks need.
+    pub fn render_with_integrity<TRendersPath: RendersPath>(
+        &self,
+        renders_path: &TRendersPath,
+        integrity: &SubresourceIntegrity,
+    ) -> String {
+        self.render_with_context(&RenderContext::new(renders_path).with_integrity(integrity))
+    }
+
+    pub fn render_with_context<TRendersPath: RendersPath>(
+        &self,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        let integrity = context.integrity_of(self.path());
+
         match self {
-            Asset::Script(path) => format!(
-                "<script async src=\"{}\" type=\"module\"></script>",
-                renders_path.render_path(path)
-            ),
-            Asset::Stylesheet(path) => format!(
-                "<link rel=\"stylesheet\" href=\"{}\">",
-                renders_path.render_path(path)
-            ),
+            Asset::Script(path) => {
+                let options = context.script_options(path);
+                let is_module = options.script_type == ScriptType::Module;
+                // module scripts are always fetched in CORS mode, classic scripts
+                // need the attribute for integrity checks
+                let cross_origin = options.cross_origin.or_else(|| {
+                    (integrity.is_some() && !is_module).then_some(CrossOrigin::Anonymous)
+                });
+                let mut tag = HtmlTag::new("script");
+
+                if let Some(loading) = options.loading.attribute_name() {
+                    tag = tag.boolean_attribute(loading);
+                }
+
+                tag = tag
+                    .attribute("src", &context.render_path(path))
+                    .optional_attribute("integrity", integrity);
+
+                tag = match cross_origin {
+                    Some(CrossOrigin::Anonymous) => tag.boolean_attribute("crossorigin"),
+                    Some(CrossOrigin::UseCredentials) => {
+                        tag.attribute("crossorigin", "use-credentials")
+                    }
+                    None => tag,
+                };
+
+                tag.optional_attribute(
+                    "fetchpriority",
+                    options.fetch_priority.map(FetchPriority::as_str),
+                )
+                .optional_attribute("nonce", context.nonce)
+                .optional_attribute("type", is_module.then_some("module"))
+                .finish_with_closing_tag()
+            }
+            Asset::Stylesheet(path) => HtmlTag::new("link")
+                .attribute("rel", "stylesheet")
+                .attribute("href", &context.render_path(path))
+                .optional_attribute("integrity", integrity)
+                .boolean_attribute_if("crossorigin", integrity.is_some())
+                .optional_attribute("nonce", context.nonce)
+                .finish(),
             Asset::Unknown(_) => String::new(),
         }
     }
@@ -36,6 +105,9 @@ impl Asset {
 mod tests {
     use super::*;
     use crate::path_renderer::PathRenderer;
+    use crate::script_loading::ScriptLoading;
+    use crate::script_options::ScriptOptions;
+    use crate::script_rules::ScriptRules;
 
     #[test]
     fn test_script_renders_module_script_tag() {
@@ -60,4 +132,154 @@ mod tests {
 
         assert_eq!(rendered, "");
     }
+
+    #[test]
+    fn test_hostile_paths_are_escaped() {
+        let hostile = "x\"><script>alert('&')</script>".to_string();
+
+        assert_eq!(
+            Asset::Script(hostile.clone()).render(&PathRenderer {}),
+            "<script async src=\"/x&quot;&gt;&lt;script&gt;alert(&#39;&amp;&#39;)&lt;/script&gt;\" type=\"module\"></script>"
+        );
+        assert_eq!(
+            Asset::Stylesheet(hostile.clone()).render(&PathRenderer {}),
+            "<link rel=\"stylesheet\" href=\"/x&quot;&gt;&lt;script&gt;alert(&#39;&amp;&#39;)&lt;/script&gt;\">"
+        );
+        assert_eq!(Asset::Unknown(hostile).render(&PathRenderer {}), "");
+    }
+
+    #[test]
+    fn test_render_with_integrity() {
+        let integrity = SubresourceIntegrity::default()
+            .with_hash("dist/app.js", "sha384-js")
+            .with_hash("dist/app.css", "sha384-css");
+
+        assert_eq!(
+            Asset::from_path("dist/app.js".to_string())
+                .render_with_integrity(&PathRenderer {}, &integrity),
+            "<script async src=\"/dist/app.js\" integrity=\"sha384-js\" type=\"module\"></script>"
+        );
+        assert_eq!(
+            Asset::from_path("dist/app.css".to_string())
+                .render_with_integrity(&PathRenderer {}, &integrity),
+            "<link rel=\"stylesheet\" href=\"/dist/app.css\" integrity=\"sha384-css\" crossorigin>"
+        );
+        assert_eq!(
+            Asset::from_path("dist/other.css".to_string())
+                .render_with_integrity(&PathRenderer {}, &integrity),
+            "<link rel=\"stylesheet\" href=\"/dist/other.css\">"
+        );
+        assert_eq!(
+            Asset::from_path("dist/model.bin".to_string())
+                .render_with_integrity(&PathRenderer {}, &integrity),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_path_returns_wrapped_path() {
+        assert_eq!(
+            Asset::Unknown("dist/a.bin".to_string()).path(),
+            "dist/a.bin"
+        );
+    }
+
+    #[test]
+    fn test_render_with_nonce() {
+        let integrity = SubresourceIntegrity::default().with_hash("dist/app.css", "sha384-css");
+        let context = RenderContext::new(&PathRenderer {})
+            .with_nonce("r4nd\"om")
+            .with_integrity(&integrity);
+
+        assert_eq!(
+            Asset::from_path("dist/app.js".to_string()).render_with_context(&context),
+            "<script async src=\"/dist/app.js\" nonce=\"r4nd&quot;om\" type=\"module\"></script>"
+        );
+        assert_eq!(
+            Asset::from_path("dist/app.css".to_string()).render_with_context(&context),
+            "<link rel=\"stylesheet\" href=\"/dist/app.css\" integrity=\"sha384-css\" crossorigin nonce=\"r4nd&quot;om\">"
+        );
+        assert_eq!(
+            Asset::from_path("dist/model.bin".to_string()).render_with_context(&context),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_render_with_script_rules() {
+        let script_rules = ScriptRules::default()
+            .with_pattern(
+                "dist/legacy*.js",
+                ScriptOptions {
+                    loading: ScriptLoading::Blocking,
+                    script_type: ScriptType::Classic,
+                    ..ScriptOptions::default()
+                },
+            )
+            .unwrap()
+            .with_pattern(
+                "dist/critical.js",
+                ScriptOptions {
+                    cross_origin: Some(CrossOrigin::UseCredentials),
+                    fetch_priority: Some(FetchPriority::High),
+                    loading: ScriptLoading::Defer,
+                    ..ScriptOptions::default()
+                },
+            )
+            .unwrap()
+            .with_pattern(
+                "dist/anonymous.js",
+                ScriptOptions {
+                    cross_origin: Some(CrossOrigin::Anonymous),
+                    fetch_priority: Some(FetchPriority::Low),
+                    ..ScriptOptions::default()
+                },
+            )
+            .unwrap();
+        let integrity = SubresourceIntegrity::default().with_hash("dist/legacy.js", "sha384-js");
+        let context = RenderContext::new(&PathRenderer {})
+            .with_integrity(&integrity)
+            .with_script_rules(&script_rules);
+        let render = |path: &str| Asset::Script(path.to_string()).render_with_context(&context);
+
+        assert_eq!(
+            render("dist/legacy.js"),
+            "<script src=\"/dist/legacy.js\" integrity=\"sha384-js\" crossorigin></script>"
+        );
+        assert_eq!(
+            render("dist/legacy-other.js"),
+            "<script src=\"/dist/legacy-other.js\"></script>"
+        );
+        assert_eq!(
+            render("dist/critical.js"),
+            "<script defer src=\"/dist/critical.js\" crossorigin=\"use-credentials\" fetchpriority=\"high\" type=\"module\"></script>"
+        );
+        assert_eq!(
+            render("dist/anonymous.js"),
+            "<script async src=\"/dist/anonymous.js\" crossorigin fetchpriority=\"low\" type=\"module\"></script>"
+        );
+        assert_eq!(
+            render("dist/app.js"),
+            "<script async src=\"/dist/app.js\" type=\"module\"></script>"
+        );
+    }
+
+    #[test]
+    fn test_from_path_with_registry() {
+        let registry =
+            AssetTypeRegistry::empty().with_extension("cjs", PreloadableAssetKind::Module, None);
+
+        assert_eq!(
+            Asset::from_path_with_registry("dist/app.cjs".to_string(), &registry),
+            Asset::Script("dist/app.cjs".to_string())
+        );
+        assert_eq!(
+            Asset::from_path_with_registry("dist/app.js".to_string(), &registry),
+            Asset::Unknown("dist/app.js".to_string())
+        );
+        assert_eq!(
+            Asset::from_path("dist/app.mjs".to_string()),
+            Asset::Script("dist/app.mjs".to_string())
+        );
+    }
 }
@@ -0,0 +1,362 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use crate::asset::Asset;
+use crate::asset_manifest::AssetManifest;
+use crate::asset_type_registry::AssetTypeRegistry;
+use crate::collected_assets::CollectedAssets;
+use crate::error::Error;
+use crate::input_lookup::InputLookup;
+use crate::output_lookup::OutputLookup;
+use crate::preloadable_asset::PreloadableAsset;
+use crate::render_context::RenderContext;
+use crate::renders_path::RendersPath;
+
+fn asset_rank(asset: &Asset) -> u8 {
+    match asset {
+        Asset::Stylesheet(_) => 0,
+        Asset::Script(_) => 1,
+        Asset::Unknown(_) => 2,
+    }
+}
+
+/// Gathers the entry points used while rendering a single page, so shared chunks,
+/// stylesheets and fonts end up in the `<head>` only once.
+///
+/// Templates call [`AssetCollector::add`] from wherever they render a component. The tags
+/// are only computed when the collector is rendered, so entries added after the head
+/// placeholder was written are still included by [`AssetCollector::replace_placeholder`].
+#[derive(Debug)]
+pub struct AssetCollector {
+    asset_types: Option<Arc<AssetTypeRegistry>>,
+    input_paths: Mutex<Vec<String>>,
+    manifest: Arc<dyn AssetManifest>,
+}
+
+impl AssetCollector {
+    pub fn new(manifest: Arc<dyn AssetManifest>) -> Self {
+        Self {
+            asset_types: None,
+            input_paths: Mutex::new(Vec::new()),
+            manifest,
+        }
+    }
+
+    /// Classifies the collected paths with `asset_types` instead of the default registry.
+    pub fn with_asset_types(mut self, asset_types: Arc<AssetTypeRegistry>) -> Self {
+        self.asset_types = Some(asset_types);
+        self
+    }
+
+    /// Resolves the input path with [`AssetManifest::resolve_input_path`]. Returns
+    /// `false` if the manifest does not know it, and an error if it is ambiguous.
+    pub fn add(&self, input_path: &str) -> Result<bool, Error> {
+        let Some(resolved_path) = self.manifest.resolve_input_path(input_path)? else {
+            return Ok(false);
+        };
+
+        let mut input_paths = self
+            .input_paths
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if !input_paths.iter().any(|added| **added == *resolved_path) {
+            input_paths.push(resolved_path.to_string());
+        }
+
+        Ok(true)
+    }
+
+    pub fn collect(&self) -> CollectedAssets {
+        let input_paths = self
+            .input_paths
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let asset_types = self
+            .asset_types
+            .as_deref()
+            .unwrap_or_else(|| AssetTypeRegistry::default_registry());
+        let mut asset_paths: HashSet<Arc<str>> = HashSet::new();
+        let mut assets: Vec<Asset> = Vec::new();
+        let mut preload_paths: HashSet<Arc<str>> = HashSet::new();
+        let mut preloads: Vec<PreloadableAsset> = Vec::new();
+        let mut output_properties = Vec::new();
+
+        for input_path in &input_paths {
+            if let InputLookup::Found(input) = self.manifest.input(input_path) {
+                for output_path in input.outputs.iter() {
+                    if let OutputLookup::Found(output) = self.manifest.output(output_path) {
+                        output_properties.push(output);
+                    }
+
+                    // Outputs that are neither scripts nor stylesheets, like images
+                    // emitted by the file loader, have no tag of their own and are
+                    // preloaded instead.
+                    match Asset::from_path_with_registry(output_path.to_string(), asset_types) {
+                        Asset::Unknown(path) => {
+                            if preload_paths.insert(output_path.clone()) {
+                                preloads.push(PreloadableAsset::from_path_with_registry(
+                                    path,
+                                    asset_types,
+                                ));
+                            }
+                        }
+                        asset => {
+                            if asset_paths.insert(output_path.clone()) {
+                                assets.push(asset);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        preload_paths.extend(asset_paths);
+
+        let mut prefetches: Vec<PreloadableAsset> = Vec::new();
+
+        for output in &output_properties {
+            for path in output.preloads.iter() {
+                if preload_paths.insert(path.clone()) {
+                    preloads.push(PreloadableAsset::from_path_with_registry(
+                        path.to_string(),
+                        asset_types,
+                    ));
+                }
+            }
+        }
+
+        for output in &output_properties {
+            for path in output.prefetches.iter() {
+                if preload_paths.insert(path.clone()) {
+                    prefetches.push(PreloadableAsset::Prefetch(path.to_string()));
+                }
+            }
+        }
+
+        assets.sort_by_key(asset_rank);
+        preloads.append(&mut prefetches);
+
+        CollectedAssets {
+            assets,
+            preloads,
+        }
+    }
+
+    pub fn render<TRendersPath: RendersPath>(&self, renders_path: &TRendersPath) -> String {
+        self.collect().render(renders_path)
+    }
+
+    pub fn render_with_context<TRendersPath: RendersPath>(
+        &self,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        self.collect().render_with_context(context)
+    }
+
+    /// Replaces the first occurrence of `placeholder` in an already rendered page with
+    /// the collected tags.
+    pub fn replace_placeholder<TRendersPath: RendersPath>(
+        &self,
+        html: &str,
+        placeholder: &str,
+        renders_path: &TRendersPath,
+    ) -> String {
+        html.replacen(placeholder, &self.render(renders_path), 1)
+    }
+
+    pub fn replace_placeholder_with_context<TRendersPath: RendersPath>(
+        &self,
+        html: &str,
+        placeholder: &str,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        html.replacen(placeholder, &self.render_with_context(context), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::esbuild_metafile::EsbuildMetafile;
+    use crate::path_renderer::PathRenderer;
+    use crate::preloadable_asset_kind::PreloadableAssetKind;
+    use crate::test::get_metafile_dynamic;
+    use crate::test::get_metafile_fonts;
+
+    #[test]
+    fn test_unknown_input_is_not_added() {
+        let collector = AssetCollector::new(get_metafile_fonts());
+
+        assert!(!collector.add("resources/ts/does_not_exist.tsx").unwrap());
+        assert_eq!(collector.collect(), CollectedAssets::default());
+        assert_eq!(collector.render(&PathRenderer {}), "");
+    }
+
+    #[test]
+    fn test_shared_preloads_are_emitted_once() {
+        let collector = AssetCollector::new(get_metafile_fonts());
+
+        assert!(collector.add("resources/ts/controller_foo.tsx").unwrap());
+        assert!(collector.add("resources/css/page-common.css").unwrap());
+        assert!(collector.add("resources/ts/controller_foo.tsx").unwrap());
+
+        assert_eq!(
+            collector.collect(),
+            CollectedAssets {
+                assets: vec![
+                    Asset::Stylesheet("static/controller_foo_CX2Z63ZH.css".to_string()),
+                    Asset::Stylesheet("static/page-common_DO3RNJ3I.css".to_string()),
+                    Asset::Script("static/controller_foo_CTJMZK66.js".to_string()),
+                ],
+                preloads: vec![
+                    PreloadableAsset::from_path("static/test_6D5OPEBZ.svg".to_string()),
+                    PreloadableAsset::Module("static/chunk-EMZKCXNJ.js".to_string()),
+                    PreloadableAsset::Module("static/chunk-PI4ZFSEL.js".to_string()),
+                    PreloadableAsset::from_path("https://fonts/font1.woff2".to_string()),
+                    PreloadableAsset::from_path("https://fonts/font3.woff2".to_string()),
+                    PreloadableAsset::from_path("static/logo_XSTJPNLH.png".to_string()),
+                    PreloadableAsset::from_path("https://fonts/font2.woff2".to_string()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_added_inputs_are_resolved() {
+        let collector = AssetCollector::new(get_metafile_fonts());
+        let exact = AssetCollector::new(get_metafile_fonts());
+
+        assert!(collector.add("./resources/ts/controller_foo.tsx").unwrap());
+        assert!(collector.add("resources\\ts\\controller_foo.tsx").unwrap());
+        assert!(exact.add("resources/ts/controller_foo.tsx").unwrap());
+        assert_eq!(collector.collect(), exact.collect());
+
+        let collector = AssetCollector::new(Arc::new(
+            EsbuildMetafile::from_str(
+                r#"{"outputs": {
+                    "dist/a.js": {"imports": [], "entryPoint": "src/a.ts"},
+                    "dist/b.js": {"imports": [], "entryPoint": "file:src/a.ts"}
+                }}"#,
+            )
+            .unwrap(),
+        ));
+
+        assert!(matches!(
+            collector.add("./src/a.ts"),
+            Err(Error::AmbiguousInput { input_path, .. }) if input_path == "./src/a.ts"
+        ));
+        assert_eq!(collector.collect(), CollectedAssets::default());
+    }
+
+    #[test]
+    fn test_prefetches_come_last_and_skip_eager_paths() {
+        let collector = AssetCollector::new(get_metafile_dynamic());
+
+        collector.add("src/main.ts").unwrap();
+        collector.add("src/lazy.ts").unwrap();
+
+        let collected = collector.collect();
+
+        assert_eq!(
+            collected.assets,
+            vec![
+                Asset::Script("dist/chunk-shared.js".to_string()),
+                Asset::Script("dist/main.js".to_string()),
+                Asset::Script("dist/chunk-lazy.js".to_string()),
+                Asset::Script("dist/lazy.js".to_string()),
+            ]
+        );
+        assert!(collected.preloads.is_empty());
+
+        let collector = AssetCollector::new(get_metafile_dynamic());
+
+        collector.add("src/main.ts").unwrap();
+
+        assert_eq!(
+            collector.collect().preloads,
+            vec![
+                PreloadableAsset::Prefetch("dist/chunk-lazy.js".to_string()),
+                PreloadableAsset::Prefetch("dist/lazy.js".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entries_added_after_placeholder_are_rendered() {
+        let collector = AssetCollector::new(get_metafile_fonts());
+        let mut html = String::from("<head><!-- assets --></head><body>");
+
+        collector.add("resources/css/page-common.css").unwrap();
+        html.push_str("<div>component</div></body>");
+        collector.add("resources/ts/controller_foo.tsx").unwrap();
+
+        assert_eq!(
+            collector.replace_placeholder(&html, "<!-- assets -->", &PathRenderer {}),
+            [
+                "<head>",
+                "<link rel=\"stylesheet\" href=\"/static/page-common_DO3RNJ3I.css\">\n",
+                "<link rel=\"stylesheet\" href=\"/static/controller_foo_CX2Z63ZH.css\">\n",
+                "<script async src=\"/static/controller_foo_CTJMZK66.js\" type=\"module\"></script>\n",
+                "<link rel=\"preload\" href=\"/static/test_6D5OPEBZ.svg\" as=\"image\" type=\"image/svg+xml\">\n",
+                "<link rel=\"preload\" href=\"https://fonts/font1.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>\n",
+                "<link rel=\"preload\" href=\"https://fonts/font2.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>\n",
+                "<link rel=\"modulepreload\" href=\"/static/chunk-EMZKCXNJ.js\">\n",
+                "<link rel=\"modulepreload\" href=\"/static/chunk-PI4ZFSEL.js\">\n",
+                "<link rel=\"preload\" href=\"https://fonts/font3.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>\n",
+                "<link rel=\"preload\" href=\"/static/logo_XSTJPNLH.png\" as=\"image\" type=\"image/png\">",
+                "</head><body><div>component</div></body>",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_replace_placeholder_with_nonce() {
+        let collector = AssetCollector::new(get_metafile_fonts());
+        let context = RenderContext::new(&PathRenderer {}).with_nonce("n0nce");
+
+        collector.add("resources/css/page-common.css").unwrap();
+
+        assert_eq!(
+            collector.replace_placeholder_with_context(
+                "<head><!-- assets --></head>",
+                "<!-- assets -->",
+                &context
+            ),
+            [
+                "<head>",
+                "<link rel=\"stylesheet\" href=\"/static/page-common_DO3RNJ3I.css\" nonce=\"n0nce\">\n",
+                "<link rel=\"preload\" href=\"/static/test_6D5OPEBZ.svg\" as=\"image\" type=\"image/svg+xml\">\n",
+                "<link rel=\"preload\" href=\"https://fonts/font1.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>\n",
+                "<link rel=\"preload\" href=\"https://fonts/font2.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>",
+                "</head>",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_custom_asset_types() {
+        let asset_types =
+            AssetTypeRegistry::default().with_extension("png", PreloadableAssetKind::Fetch, None);
+        let collector =
+            AssetCollector::new(get_metafile_fonts()).with_asset_types(Arc::new(asset_types));
+
+        collector.add("resources/ts/controller_foo.tsx").unwrap();
+
+        assert!(
+            collector
+                .collect()
+                .preloads
+                .contains(&PreloadableAsset::Fetch(
+                    "static/logo_XSTJPNLH.png".to_string()
+                ))
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::input_lookup::InputLookup;
+use crate::output_lookup::OutputLookup;
+
+/// Resolves entry points to their outputs, preloads and static paths, independently of
+/// the bundler that produced them.
+///
+/// Implemented by [`EsbuildMetafile`](crate::esbuild_metafile::EsbuildMetafile),
+/// [`ViteAssetManifest`](crate::vite_asset_manifest::ViteAssetManifest) and
+/// [`StaticAssetManifest`](crate::static_asset_manifest::StaticAssetManifest).
+pub trait AssetManifest: Debug + Send + Sync {
+    /// Stylesheets and scripts an entry point needs, or the files a static input was
+    /// copied to.
+    fn input(&self, input_path: &str) -> InputLookup;
+
+    /// Preloads and prefetches of an output.
+    fn output(&self, output_path: &str) -> OutputLookup;
+
+    /// The script or stylesheet generated for the entry point itself, without the chunks
+    /// and stylesheets it pulls in.
+    fn entry_output(&self, input_path: &str) -> Option<Arc<str>>;
+
+    /// The input path as the manifest knows it, for a path written by hand. Matches
+    /// exactly unless the manifest knows better, like
+    /// [`EsbuildMetafile::resolve_input`](crate::esbuild_metafile::EsbuildMetafile::resolve_input).
+    fn resolve_input_path(&self, input_path: &str) -> Result<Option<Arc<str>>, Error> {
+        Ok(match self.input(input_path) {
+            InputLookup::Found(_) => Some(Arc::from(input_path)),
+            InputLookup::NotFound => None,
+        })
+    }
+
+    /// [`AssetManifest::input`] of the path [`AssetManifest::resolve_input_path`] finds.
+    fn resolve_input(&self, input_path: &str) -> Result<InputLookup, Error> {
+        Ok(match self.resolve_input_path(input_path)? {
+            Some(resolved_path) => self.input(&resolved_path),
+            None => InputLookup::NotFound,
+        })
+    }
+}
+
+impl<TAssetManifest: AssetManifest + ?Sized> AssetManifest for Arc<TAssetManifest> {
+    fn input(&self, input_path: &str) -> InputLookup {
+        self.as_ref().input(input_path)
+    }
+
+    fn output(&self, output_path: &str) -> OutputLookup {
+        self.as_ref().output(output_path)
+    }
+
+    fn entry_output(&self, input_path: &str) -> Option<Arc<str>> {
+        self.as_ref().entry_output(input_path)
+    }
+
+    fn resolve_input_path(&self, input_path: &str) -> Result<Option<Arc<str>>, Error> {
+        self.as_ref().resolve_input_path(input_path)
+    }
+}
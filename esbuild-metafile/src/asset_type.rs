@@ -0,0 +1,8 @@
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+
+/// How files with a given extension are preloaded, and their MIME type if known.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetType {
+    pub kind: PreloadableAssetKind,
+    pub mime_type: Option<String>,
+}
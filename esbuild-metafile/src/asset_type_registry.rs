@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::asset_type::AssetType;
+use crate::filesystem::get_file_extension;
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+
+const DEFAULT_ASSET_TYPES: &[(&str, PreloadableAssetKind, &str)] = &[
+    ("avif", PreloadableAssetKind::Image, "image/avif"),
+    ("css", PreloadableAssetKind::Stylesheet, "text/css"),
+    ("flac", PreloadableAssetKind::Audio, "audio/flac"),
+    ("gif", PreloadableAssetKind::Image, "image/gif"),
+    ("glb", PreloadableAssetKind::Fetch, "model/gltf-binary"),
+    ("gltf", PreloadableAssetKind::Fetch, "model/gltf+json"),
+    ("ico", PreloadableAssetKind::Image, "image/x-icon"),
+    ("jpeg", PreloadableAssetKind::Image, "image/jpeg"),
+    ("jpg", PreloadableAssetKind::Image, "image/jpeg"),
+    ("js", PreloadableAssetKind::Module, "text/javascript"),
+    ("json", PreloadableAssetKind::Json, "application/json"),
+    ("m4a", PreloadableAssetKind::Audio, "audio/mp4"),
+    ("mjs", PreloadableAssetKind::Module, "text/javascript"),
+    ("mp3", PreloadableAssetKind::Audio, "audio/mpeg"),
+    ("mp4", PreloadableAssetKind::Video, "video/mp4"),
+    ("oga", PreloadableAssetKind::Audio, "audio/ogg"),
+    ("ogg", PreloadableAssetKind::Audio, "audio/ogg"),
+    ("ogv", PreloadableAssetKind::Video, "video/ogg"),
+    ("opus", PreloadableAssetKind::Audio, "audio/ogg"),
+    ("otf", PreloadableAssetKind::Font, "font/otf"),
+    ("png", PreloadableAssetKind::Image, "image/png"),
+    ("svg", PreloadableAssetKind::Image, "image/svg+xml"),
+    ("ttf", PreloadableAssetKind::Font, "font/ttf"),
+    ("wasm", PreloadableAssetKind::Wasm, "application/wasm"),
+    ("wav", PreloadableAssetKind::Audio, "audio/wav"),
+    ("webm", PreloadableAssetKind::Video, "video/webm"),
+    ("webp", PreloadableAssetKind::Image, "image/webp"),
+    ("woff", PreloadableAssetKind::Font, "font/woff"),
+    ("woff2", PreloadableAssetKind::Font, "font/woff2"),
+];
+
+static DEFAULT_REGISTRY: LazyLock<AssetTypeRegistry> = LazyLock::new(AssetTypeRegistry::default);
+
+/// Maps file extensions to [`AssetType`]s. Extensions are matched case-insensitively,
+/// paths with an unknown extension are preloaded with `fetch`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetTypeRegistry {
+    asset_types: HashMap<String, AssetType>,
+}
+
+impl AssetTypeRegistry {
+    /// The registry used when no other one is configured.
+    pub fn default_registry() -> &'static AssetTypeRegistry {
+        &DEFAULT_REGISTRY
+    }
+
+    /// A registry without any extensions, for users who want to list them all.
+    pub fn empty() -> Self {
+        Self {
+            asset_types: HashMap::new(),
+        }
+    }
+
+    /// Adds or replaces the type of an extension, given without the leading dot.
+    pub fn with_extension(
+        mut self,
+        extension: &str,
+        kind: PreloadableAssetKind,
+        mime_type: Option<&str>,
+    ) -> Self {
+        self.asset_types.insert(
+            extension.to_ascii_lowercase(),
+            AssetType {
+                kind,
+                mime_type: mime_type.map(str::to_string),
+            },
+        );
+        self
+    }
+
+    pub fn asset_type(&self, path: &str) -> Option<&AssetType> {
+        get_file_extension(path)
+            .and_then(|extension| self.asset_types.get(&extension.to_ascii_lowercase()))
+    }
+
+    pub fn kind(&self, path: &str) -> PreloadableAssetKind {
+        self.asset_type(path)
+            .map_or(PreloadableAssetKind::Fetch, |asset_type| asset_type.kind)
+    }
+
+    pub fn mime_type(&self, path: &str) -> Option<&str> {
+        self.asset_type(path)
+            .and_then(|asset_type| asset_type.mime_type.as_deref())
+    }
+}
+
+impl Default for AssetTypeRegistry {
+    fn default() -> Self {
+        DEFAULT_ASSET_TYPES
+            .iter()
+            .fold(Self::empty(), |registry, (extension, kind, mime_type)| {
+                registry.with_extension(extension, *kind, Some(mime_type))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_kinds() {
+        let registry = AssetTypeRegistry::default_registry();
+
+        assert_eq!(registry.kind("a.js"), PreloadableAssetKind::Module);
+        assert_eq!(registry.kind("a.WOFF2"), PreloadableAssetKind::Font);
+        assert_eq!(registry.kind("a.ico"), PreloadableAssetKind::Image);
+        assert_eq!(registry.kind("a.mp4"), PreloadableAssetKind::Video);
+        assert_eq!(registry.kind("a.mp3"), PreloadableAssetKind::Audio);
+        assert_eq!(registry.kind("a.wasm"), PreloadableAssetKind::Wasm);
+        assert_eq!(registry.kind("a.json"), PreloadableAssetKind::Json);
+        assert_eq!(registry.kind("a.glb"), PreloadableAssetKind::Fetch);
+        assert_eq!(registry.kind("a.unknown"), PreloadableAssetKind::Fetch);
+        assert_eq!(registry.kind("a"), PreloadableAssetKind::Fetch);
+    }
+
+    #[test]
+    fn test_default_mime_types() {
+        let registry = AssetTypeRegistry::default_registry();
+
+        assert_eq!(registry.mime_type("a.woff2"), Some("font/woff2"));
+        assert_eq!(registry.mime_type("a.glb"), Some("model/gltf-binary"));
+        assert_eq!(registry.mime_type("a.unknown"), None);
+    }
+
+    #[test]
+    fn test_extensions_can_be_overridden() {
+        let registry = AssetTypeRegistry::default()
+            .with_extension("glb", PreloadableAssetKind::Image, None)
+            .with_extension(
+                "BIN",
+                PreloadableAssetKind::Fetch,
+                Some("application/x-bin"),
+            );
+
+        assert_eq!(registry.kind("a.glb"), PreloadableAssetKind::Image);
+        assert_eq!(registry.mime_type("a.glb"), None);
+        assert_eq!(registry.mime_type("a.bin"), Some("application/x-bin"));
+        assert_eq!(registry.kind("a.js"), PreloadableAssetKind::Module);
+        assert_eq!(
+            AssetTypeRegistry::empty().kind("a.js"),
+            PreloadableAssetKind::Fetch
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::budget_result::BudgetResult;
+use crate::byte_size::format_bytes;
+use crate::size_budget::SizeBudget;
+
+/// Every result of checking a metafile against
+/// [`SizeBudgets`](crate::size_budgets::SizeBudgets).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct BudgetEvaluation {
+    pub results: Vec<BudgetResult>,
+    /// Budgets whose pattern matched nothing, usually because of a typo or a renamed
+    /// entry point. They fail the evaluation.
+    pub unmatched: Vec<SizeBudget>,
+}
+
+impl BudgetEvaluation {
+    pub fn passed(&self) -> bool {
+        self.unmatched.is_empty() && self.results.iter().all(|result| result.passed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &BudgetResult> {
+        self.results.iter().filter(|result| !result.passed)
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut text = String::new();
+
+        for result in &self.results {
+            let _ = write!(
+                text,
+                "{} {} {}: {} ",
+                if result.passed {
+                    "pass"
+                } else {
+                    "FAIL"
+                },
+                result.budget.kind.as_str(),
+                result.path,
+                format_bytes(result.actual_bytes),
+            );
+
+            if result.passed {
+                let _ = writeln!(text, "<= {}", format_bytes(result.budget.max_bytes));
+            } else {
+                let _ = writeln!(
+                    text,
+                    "> {} (+{})",
+                    format_bytes(result.budget.max_bytes),
+                    format_bytes(result.overshoot_bytes)
+                );
+            }
+        }
+
+        for budget in &self.unmatched {
+            let _ = writeln!(
+                text,
+                "FAIL {} {}: matches nothing",
+                budget.kind.as_str(),
+                budget.pattern
+            );
+        }
+
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("budget evaluation always serializes")
+    }
+}
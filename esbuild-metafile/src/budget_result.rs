@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+use crate::size_budget::SizeBudget;
+
+/// The outcome of checking one entry point or output against a [`SizeBudget`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetResult {
+    pub budget: SizeBudget,
+    /// The entry point or output path the budget was checked against.
+    pub path: String,
+    pub actual_bytes: u64,
+    pub passed: bool,
+    /// How many bytes over the budget it is, `0` if it passed.
+    pub overshoot_bytes: u64,
+}
+
+impl BudgetResult {
+    pub fn new(budget: &SizeBudget, path: &str, actual_bytes: u64) -> Self {
+        Self {
+            budget: budget.clone(),
+            path: path.to_string(),
+            actual_bytes,
+            passed: actual_bytes <= budget.max_bytes,
+            overshoot_bytes: actual_bytes.saturating_sub(budget.max_bytes),
+        }
+    }
+}
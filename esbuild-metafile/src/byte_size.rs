@@ -0,0 +1,45 @@
+/// Formats a byte count for people, using binary units above one kibibyte.
+pub fn format_bytes(bytes: u64) -> String {
+    const KIBIBYTE: u64 = 1024;
+    const MEBIBYTE: u64 = KIBIBYTE * 1024;
+
+    if bytes >= MEBIBYTE {
+        format!("{:.1} MiB", bytes as f64 / MEBIBYTE as f64)
+    } else if bytes >= KIBIBYTE {
+        format!("{:.1} KiB", bytes as f64 / KIBIBYTE as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Formats a change in size with an explicit sign, like `+1.3 KiB` or `-200 B`.
+pub fn format_byte_delta(delta: i64) -> String {
+    let sign = match delta.signum() {
+        1 => "+",
+        -1 => "-",
+        _ => "",
+    };
+
+    format!("{sign}{}", format_bytes(delta.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(55_000), "53.7 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MiB");
+    }
+
+    #[test]
+    fn test_format_byte_delta() {
+        assert_eq!(format_byte_delta(0), "0 B");
+        assert_eq!(format_byte_delta(6_100), "+6.0 KiB");
+        assert_eq!(format_byte_delta(-200), "-200 B");
+    }
+}
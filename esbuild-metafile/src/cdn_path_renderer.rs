@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::asset_type_registry::AssetTypeRegistry;
+use crate::filesystem::is_absolute_url;
+use crate::filesystem::is_data_url;
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+use crate::renders_path::RendersPath;
+
+fn join_base(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+/// Renders output paths for assets served from a CDN or another base path.
+///
+/// Paths are made relative to the build directory by stripping an optional prefix,
+/// then joined with the base of their [`PreloadableAssetKind`], or with the default
+/// base. Absolute, protocol-relative and `data:` URLs are left untouched.
+#[derive(Clone, Debug)]
+pub struct CdnPathRenderer {
+    asset_types: Option<Arc<AssetTypeRegistry>>,
+    base: String,
+    bases_by_kind: HashMap<PreloadableAssetKind, String>,
+    strip_prefix: Option<String>,
+}
+
+impl CdnPathRenderer {
+    /// `base` can be a URL like `https://cdn.example.com/assets` or a path like `/assets`.
+    pub fn new(base: &str) -> Self {
+        Self {
+            asset_types: None,
+            base: base.to_string(),
+            bases_by_kind: HashMap::new(),
+            strip_prefix: None,
+        }
+    }
+
+    /// Classifies paths with `asset_types` instead of the default registry.
+    pub fn with_asset_types(mut self, asset_types: Arc<AssetTypeRegistry>) -> Self {
+        self.asset_types = Some(asset_types);
+        self
+    }
+
+    pub fn with_base_for(mut self, kind: PreloadableAssetKind, base: &str) -> Self {
+        self.bases_by_kind.insert(kind, base.to_string());
+        self
+    }
+
+    /// Removes the build directory, like `static/`, from the start of output paths.
+    pub fn with_strip_prefix(mut self, strip_prefix: &str) -> Self {
+        self.strip_prefix = Some(strip_prefix.to_string());
+        self
+    }
+}
+
+impl RendersPath for CdnPathRenderer {
+    fn render_path(&self, path: &str) -> String {
+        if is_absolute_url(path) || is_data_url(path) {
+            return path.to_string();
+        }
+
+        let kind = self
+            .asset_types
+            .as_deref()
+            .unwrap_or_else(|| AssetTypeRegistry::default_registry())
+            .kind(path);
+        let base = self.bases_by_kind.get(&kind).unwrap_or(&self.base);
+        let relative_path = self
+            .strip_prefix
+            .as_deref()
+            .and_then(|strip_prefix| path.strip_prefix(strip_prefix))
+            .unwrap_or(path);
+
+        join_base(base, relative_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_prefix_and_base_url() {
+        let renderer =
+            CdnPathRenderer::new("https://cdn.example.com/assets/").with_strip_prefix("static/");
+
+        assert_eq!(
+            renderer.render_path("static/controller_foo_CTJMZK66.js"),
+            "https://cdn.example.com/assets/controller_foo_CTJMZK66.js"
+        );
+        assert_eq!(
+            renderer.render_path("other/app.js"),
+            "https://cdn.example.com/assets/other/app.js"
+        );
+    }
+
+    #[test]
+    fn test_base_path() {
+        let renderer = CdnPathRenderer::new("/assets");
+
+        assert_eq!(renderer.render_path("static/a.css"), "/assets/static/a.css");
+        assert_eq!(CdnPathRenderer::new("/").render_path("a.css"), "/a.css");
+        assert_eq!(CdnPathRenderer::new("").render_path("a.css"), "/a.css");
+    }
+
+    #[test]
+    fn test_kinds_are_routed_to_their_own_base() {
+        let renderer = CdnPathRenderer::new("https://cdn.example.com")
+            .with_strip_prefix("static/")
+            .with_base_for(PreloadableAssetKind::Font, "https://fonts.example.com")
+            .with_base_for(PreloadableAssetKind::Image, "//images.example.com/");
+
+        assert_eq!(
+            renderer.render_path("static/Roboto.woff2"),
+            "https://fonts.example.com/Roboto.woff2"
+        );
+        assert_eq!(
+            renderer.render_path("static/logo.png"),
+            "//images.example.com/logo.png"
+        );
+        assert_eq!(
+            renderer.render_path("static/app.js"),
+            "https://cdn.example.com/app.js"
+        );
+    }
+
+    #[test]
+    fn test_custom_asset_types_are_routed() {
+        let asset_types =
+            AssetTypeRegistry::default().with_extension("glb", PreloadableAssetKind::Image, None);
+        let renderer = CdnPathRenderer::new("/assets")
+            .with_asset_types(Arc::new(asset_types))
+            .with_base_for(PreloadableAssetKind::Image, "https://images.example.com");
+
+        assert_eq!(
+            renderer.render_path("dist/model.glb"),
+            "https://images.example.com/dist/model.glb"
+        );
+    }
+
+    #[test]
+    fn test_urls_are_passed_through() {
+        let renderer = CdnPathRenderer::new("https://cdn.example.com");
+
+        for url in [
+            "https://fonts/font1.woff2",
+            "http://fonts/font1.woff2",
+            "HTTPS://fonts/font1.woff2",
+            "//fonts/font1.woff2",
+            "data:image/svg+xml,%3Csvg%3E",
+        ] {
+            assert_eq!(renderer.render_path(url), url);
+        }
+    }
+}
@@ -0,0 +1,801 @@
+use std::error::Error as StdError;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Parser;
+use clap::Subcommand;
+use serde_json::json;
+
+use crate::asset_collector::AssetCollector;
+use crate::error::Error;
+use crate::esbuild_metafile::EsbuildMetafile;
+use crate::input_lookup::InputLookup;
+use crate::metafile_diff::MetafileDiff;
+use crate::output_lookup::OutputLookup;
+use crate::path_renderer::PathRenderer;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+use crate::size_budget::SizeBudget;
+use crate::size_budgets::SizeBudgets;
+use crate::size_report::DEFAULT_TOP_INPUTS;
+use crate::size_report::SizeReport;
+
+const EXIT_SUCCESS: u8 = 0;
+const EXIT_PROBLEMS: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+/// Inspect and query esbuild metafiles with the same lookups the library uses.
+#[derive(Debug, Parser)]
+#[command(name = "esbuild-metafile", version)]
+pub struct Cli {
+    /// Path to the metafile esbuild wrote with `--metafile`.
+    #[arg(short, long)]
+    metafile: PathBuf,
+    /// Print JSON instead of human-readable output.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check sizes against the budgets in a JSON file, exiting with a non-zero status if
+    /// any is exceeded.
+    Budgets {
+        /// JSON list of budgets, like
+        /// `[{"kind": "output", "pattern": "**/*.js", "maxBytes": 81920}]`.
+        budgets_path: PathBuf,
+    },
+    /// Compare the metafile with the one from another build, in Markdown unless `--json`
+    /// is given.
+    Diff {
+        /// Metafile of the build to compare against, e.g. from a branch.
+        after_path: PathBuf,
+    },
+    /// List entry points and their entry outputs.
+    Entries,
+    /// Show the outputs and static paths of an input, which may be written like
+    /// `./src\main.ts` or `file:src/main.ts`.
+    Input {
+        input_path: String,
+    },
+    /// Show the preloads and prefetches of an output.
+    Output {
+        output_path: String,
+    },
+    /// Print the tags rendered for one or more inputs, which are resolved like for
+    /// `input`.
+    Render {
+        #[arg(required = true)]
+        input_paths: Vec<String>,
+    },
+    /// Report the size of every entry point and its stylesheets and chunks.
+    Sizes {
+        /// How many of the biggest contributing inputs to list per entry point.
+        #[arg(long, default_value_t = DEFAULT_TOP_INPUTS)]
+        top: usize,
+    },
+    /// Check the output graph, exiting with a non-zero status if there are problems.
+    Validate,
+}
+
+fn write_json<TWrite: Write>(stdout: &mut TWrite, value: &serde_json::Value) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *stdout, value)?;
+    writeln!(stdout)
+}
+
+fn write_section<TWrite: Write, TPath: AsRef<str>>(
+    stdout: &mut TWrite,
+    title: &str,
+    paths: &[TPath],
+) -> io::Result<()> {
+    writeln!(stdout, "{title}:")?;
+
+    if paths.is_empty() {
+        writeln!(stdout, "  (none)")?;
+    }
+
+    for path in paths {
+        writeln!(stdout, "  {}", path.as_ref())?;
+    }
+
+    Ok(())
+}
+
+fn write_error<TWrite: Write>(stderr: &mut TWrite, error: &Error) -> io::Result<()> {
+    writeln!(stderr, "error: {error}")?;
+
+    let mut source = error.source();
+
+    while let Some(cause) = source {
+        writeln!(stderr, "  caused by: {cause}")?;
+        source = cause.source();
+    }
+
+    Ok(())
+}
+
+fn load_budgets(budgets_path: &Path) -> Result<SizeBudgets, Box<dyn StdError>> {
+    let contents = fs::read(budgets_path)?;
+    let budgets: Vec<SizeBudget> = serde_json::from_slice(&contents)?;
+
+    Ok(SizeBudgets::from_budgets(budgets)?)
+}
+
+fn budgets<TOut: Write, TErr: Write>(
+    raw_metafile: &RawEsbuildMetafile,
+    budgets_path: &Path,
+    json: bool,
+    stdout: &mut TOut,
+    stderr: &mut TErr,
+) -> io::Result<u8> {
+    let budgets = match load_budgets(budgets_path) {
+        Ok(budgets) => budgets,
+        Err(error) => {
+            writeln!(
+                stderr,
+                "error: failed to load size budgets from \"{}\"",
+                budgets_path.display()
+            )?;
+            writeln!(stderr, "  caused by: {error}")?;
+
+            return Ok(EXIT_ERROR);
+        }
+    };
+    let evaluation = budgets.evaluate(raw_metafile, &EsbuildMetafile::from(raw_metafile));
+
+    if json {
+        writeln!(stdout, "{}", evaluation.to_json())?;
+    } else {
+        write!(stdout, "{}", evaluation.render_text())?;
+    }
+
+    Ok(if evaluation.passed() {
+        EXIT_SUCCESS
+    } else {
+        EXIT_PROBLEMS
+    })
+}
+
+fn diff<TOut: Write, TErr: Write>(
+    raw_metafile: &RawEsbuildMetafile,
+    after_path: &Path,
+    json: bool,
+    stdout: &mut TOut,
+    stderr: &mut TErr,
+) -> io::Result<u8> {
+    let after = match RawEsbuildMetafile::from_path(after_path) {
+        Ok(after) => after,
+        Err(error) => {
+            write_error(stderr, &error)?;
+
+            return Ok(EXIT_ERROR);
+        }
+    };
+    let diff = MetafileDiff::from_raw(raw_metafile, &after);
+
+    if json {
+        writeln!(stdout, "{}", diff.to_json())?;
+    } else {
+        write!(stdout, "{}", diff.render_markdown())?;
+    }
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn entries<TWrite: Write>(
+    metafile: &EsbuildMetafile,
+    json: bool,
+    stdout: &mut TWrite,
+) -> io::Result<u8> {
+    let entry_points = metafile.entry_points();
+
+    if json {
+        let entry_points: Vec<serde_json::Value> = entry_points
+            .iter()
+            .map(|(input_path, output_path)| json!({"input": input_path, "output": output_path}))
+            .collect();
+
+        write_json(stdout, &json!(entry_points))?;
+    } else {
+        for (input_path, output_path) in entry_points {
+            writeln!(stdout, "{input_path} -> {output_path}")?;
+        }
+    }
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn input<TOut: Write, TErr: Write>(
+    metafile: &EsbuildMetafile,
+    input_path: &str,
+    json: bool,
+    stdout: &mut TOut,
+    stderr: &mut TErr,
+) -> io::Result<u8> {
+    let lookup = match metafile.resolve_input(input_path) {
+        Ok(lookup) => lookup,
+        Err(error) => {
+            write_error(stderr, &error)?;
+
+            return Ok(EXIT_PROBLEMS);
+        }
+    };
+    let InputLookup::Found(input) = lookup else {
+        writeln!(
+            stderr,
+            "error: input \"{input_path}\" is not in the metafile"
+        )?;
+
+        return Ok(EXIT_PROBLEMS);
+    };
+
+    if json {
+        write_json(
+            stdout,
+            &json!({"outputs": input.outputs, "staticPaths": input.static_paths}),
+        )?;
+    } else {
+        write_section(stdout, "outputs", &input.outputs)?;
+        write_section(stdout, "static paths", &input.static_paths)?;
+    }
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn output<TOut: Write, TErr: Write>(
+    metafile: &EsbuildMetafile,
+    output_path: &str,
+    json: bool,
+    stdout: &mut TOut,
+    stderr: &mut TErr,
+) -> io::Result<u8> {
+    let OutputLookup::Found(output) = metafile.output(output_path) else {
+        writeln!(
+            stderr,
+            "error: output \"{output_path}\" is not in the metafile"
+        )?;
+
+        return Ok(EXIT_PROBLEMS);
+    };
+
+    if json {
+        write_json(
+            stdout,
+            &json!({"preloads": output.preloads, "prefetches": output.prefetches}),
+        )?;
+    } else {
+        write_section(stdout, "preloads", &output.preloads)?;
+        write_section(stdout, "prefetches", &output.prefetches)?;
+    }
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn render<TOut: Write, TErr: Write>(
+    metafile: Arc<EsbuildMetafile>,
+    input_paths: &[String],
+    json: bool,
+    stdout: &mut TOut,
+    stderr: &mut TErr,
+) -> io::Result<u8> {
+    let collector = AssetCollector::new(metafile);
+
+    for input_path in input_paths {
+        match collector.add(input_path) {
+            Ok(true) => {}
+            Ok(false) => {
+                writeln!(
+                    stderr,
+                    "error: input \"{input_path}\" is not in the metafile"
+                )?;
+
+                return Ok(EXIT_PROBLEMS);
+            }
+            Err(error) => {
+                write_error(stderr, &error)?;
+
+                return Ok(EXIT_PROBLEMS);
+            }
+        }
+    }
+
+    if json {
+        let collected = collector.collect();
+        let tags = |tags: Vec<(&str, String)>| -> Vec<serde_json::Value> {
+            tags.into_iter()
+                .filter(|(_, tag)| !tag.is_empty())
+                .map(|(path, tag)| json!({"path": path, "tag": tag}))
+                .collect()
+        };
+
+        write_json(
+            stdout,
+            &json!({
+                "assets": tags(
+                    collected
+                        .assets
+                        .iter()
+                        .map(|asset| (asset.path(), asset.render(&PathRenderer {})))
+                        .collect()
+                ),
+                "preloads": tags(
+                    collected
+                        .preloads
+                        .iter()
+                        .map(|preload| (preload.path(), preload.render(&PathRenderer {})))
+                        .collect()
+                ),
+            }),
+        )?;
+    } else {
+        writeln!(stdout, "{}", collector.render(&PathRenderer {}))?;
+    }
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn sizes<TWrite: Write>(
+    raw_metafile: &RawEsbuildMetafile,
+    top: usize,
+    json: bool,
+    stdout: &mut TWrite,
+) -> io::Result<u8> {
+    let report = SizeReport::new(raw_metafile, &EsbuildMetafile::from(raw_metafile), top);
+
+    if json {
+        writeln!(stdout, "{}", report.to_json())?;
+    } else {
+        write!(stdout, "{}", report.render_text())?;
+    }
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn validate<TWrite: Write>(
+    metafile: &RawEsbuildMetafile,
+    json: bool,
+    stdout: &mut TWrite,
+) -> io::Result<u8> {
+    let diagnostics = metafile.validate();
+
+    if json {
+        write_json(stdout, &json!(diagnostics))?;
+    } else if diagnostics.is_empty() {
+        writeln!(stdout, "no problems found")?;
+    } else {
+        for diagnostic in &diagnostics {
+            writeln!(stdout, "{diagnostic}")?;
+        }
+
+        writeln!(stdout, "{} problem(s) found", diagnostics.len())?;
+    }
+
+    Ok(if diagnostics.is_empty() {
+        EXIT_SUCCESS
+    } else {
+        EXIT_PROBLEMS
+    })
+}
+
+fn execute<TOut: Write, TErr: Write>(
+    cli: Cli,
+    stdout: &mut TOut,
+    stderr: &mut TErr,
+) -> io::Result<u8> {
+    let raw_metafile = match RawEsbuildMetafile::from_path(&cli.metafile) {
+        Ok(raw_metafile) => raw_metafile,
+        Err(error) => {
+            write_error(stderr, &error)?;
+
+            return Ok(EXIT_ERROR);
+        }
+    };
+
+    match &cli.command {
+        Command::Budgets {
+            budgets_path,
+        } => budgets(&raw_metafile, budgets_path, cli.json, stdout, stderr),
+        Command::Diff {
+            after_path,
+        } => diff(&raw_metafile, after_path, cli.json, stdout, stderr),
+        Command::Entries => entries(&EsbuildMetafile::from(raw_metafile), cli.json, stdout),
+        Command::Input {
+            input_path,
+        } => input(
+            &EsbuildMetafile::from(raw_metafile),
+            input_path,
+            cli.json,
+            stdout,
+            stderr,
+        ),
+        Command::Output {
+            output_path,
+        } => output(
+            &EsbuildMetafile::from(raw_metafile),
+            output_path,
+            cli.json,
+            stdout,
+            stderr,
+        ),
+        Command::Render {
+            input_paths,
+        } => render(
+            Arc::new(EsbuildMetafile::from(raw_metafile)),
+            input_paths,
+            cli.json,
+            stdout,
+            stderr,
+        ),
+        Command::Sizes {
+            top,
+        } => sizes(&raw_metafile, *top, cli.json, stdout),
+        Command::Validate => validate(&raw_metafile, cli.json, stdout),
+    }
+}
+
+/// Runs the command line tool and returns its exit status: `0` on success, `1` if the
+/// metafile has problems or a path is not in it, and `2` if it could not be used at all.
+pub fn run<TArgs, TArg, TOut, TErr>(args: TArgs, stdout: &mut TOut, stderr: &mut TErr) -> u8
+where
+    TArgs: IntoIterator<Item = TArg>,
+    TArg: Into<OsString> + Clone,
+    TOut: Write,
+    TErr: Write,
+{
+    let result = match Cli::try_parse_from(args) {
+        Ok(cli) => execute(cli, stdout, stderr),
+        Err(error) => {
+            let rendered = error.render();
+            let written = if error.use_stderr() {
+                write!(stderr, "{rendered}")
+            } else {
+                write!(stdout, "{rendered}")
+            };
+
+            written.map(|_| error.exit_code().try_into().unwrap_or(EXIT_ERROR))
+        }
+    };
+
+    result.unwrap_or(EXIT_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!("{}/src/test/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn run_cli(args: &[&str]) -> (u8, String, String) {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let status = run(
+            ["esbuild-metafile"].iter().chain(args),
+            &mut stdout,
+            &mut stderr,
+        );
+
+        (
+            status,
+            String::from_utf8(stdout).unwrap(),
+            String::from_utf8(stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_budgets() {
+        let sizes = fixture("esbuild-meta-sizes.json");
+        let mut budgets = tempfile::NamedTempFile::new().unwrap();
+
+        budgets
+            .write_all(
+                br#"[{"kind": "entry-closure", "pattern": "src/app.ts", "maxBytes": 50000}]"#,
+            )
+            .unwrap();
+
+        let budgets_path = budgets.path().to_str().unwrap();
+
+        assert_eq!(
+            run_cli(&["-m", &sizes, "budgets", budgets_path]),
+            (
+                1,
+                "FAIL entry-closure src/app.ts: 53.7 KiB > 48.8 KiB (+4.9 KiB)\n".to_string(),
+                String::new()
+            )
+        );
+
+        let (status, stdout, _) = run_cli(&["-m", &sizes, "--json", "budgets", budgets_path]);
+        let evaluation: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(status, 1);
+        assert_eq!(evaluation["results"][0]["overshootBytes"], 5_000);
+
+        fs::write(budgets_path, "[]").unwrap();
+
+        assert_eq!(run_cli(&["-m", &sizes, "budgets", budgets_path]).0, 0);
+
+        let (status, _, stderr) = run_cli(&["-m", &sizes, "budgets", &fixture("missing.json")]);
+
+        assert_eq!(status, 2);
+        assert!(
+            stderr.starts_with("error: failed to load size budgets from"),
+            "{stderr}"
+        );
+        assert!(stderr.contains("  caused by: "), "{stderr}");
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = fixture("esbuild-meta-sizes.json");
+        let after = fixture("esbuild-meta-sizes-next.json");
+        let (status, stdout, _) = run_cli(&["-m", &before, "diff", &after]);
+
+        assert_eq!(status, 0);
+        assert!(
+            stdout.contains("\n### New shared chunks\n\n- `dist/chunk-UI.js`\n"),
+            "{stdout}"
+        );
+
+        let (status, stdout, _) = run_cli(&["-m", &before, "diff", "--json", &after]);
+        let diff: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(status, 0);
+        assert_eq!(diff["addedEntries"], json!(["src/settings.ts"]));
+
+        let (status, _, stderr) = run_cli(&["-m", &before, "diff", &fixture("missing.json")]);
+
+        assert_eq!(status, 2);
+        assert!(
+            stderr.starts_with("error: failed to read esbuild metafile"),
+            "{stderr}"
+        );
+    }
+
+    #[test]
+    fn test_entries() {
+        let fonts = fixture("esbuild-meta-fonts.json");
+
+        assert_eq!(
+            run_cli(&["-m", &fonts, "entries"]),
+            (
+                0,
+                "resources/css/page-common.css -> static/page-common_DO3RNJ3I.css\nresources/ts/controller_foo.tsx -> static/controller_foo_CTJMZK66.js\n".to_string(),
+                String::new()
+            )
+        );
+
+        let (status, stdout, _) = run_cli(&["-m", &fonts, "entries", "--json"]);
+        let entries: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(status, 0);
+        assert_eq!(
+            entries[1],
+            json!({"input": "resources/ts/controller_foo.tsx", "output": "static/controller_foo_CTJMZK66.js"})
+        );
+    }
+
+    #[test]
+    fn test_input() {
+        let dynamic = fixture("esbuild-meta-dynamic.json");
+
+        assert_eq!(
+            run_cli(&["-m", &dynamic, "input", "src/main.ts"]),
+            (
+                0,
+                "outputs:\n  dist/chunk-shared.js\n  dist/main.js\nstatic paths:\n  (none)\n"
+                    .to_string(),
+                String::new()
+            )
+        );
+
+        let (status, stdout, _) =
+            run_cli(&["-m", &dynamic, "--json", "input", "file:./src/main.ts"]);
+
+        assert_eq!(status, 0);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&stdout).unwrap(),
+            json!({"outputs": ["dist/chunk-shared.js", "dist/main.js"], "staticPaths": []})
+        );
+        assert_eq!(
+            run_cli(&["-m", &dynamic, "input", "src/missing.ts"]),
+            (
+                1,
+                String::new(),
+                "error: input \"src/missing.ts\" is not in the metafile\n".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_input_ambiguous() {
+        let mut metafile = tempfile::NamedTempFile::new().unwrap();
+
+        metafile
+            .write_all(
+                br#"{"outputs": {
+                    "dist/a.js": {"imports": [], "entryPoint": "src/a.ts", "inputs": {}},
+                    "dist/b.js": {"imports": [], "entryPoint": "file:src/a.ts", "inputs": {}}
+                }}"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            run_cli(&[
+                "-m",
+                metafile.path().to_str().unwrap(),
+                "input",
+                "./src/a.ts"
+            ]),
+            (
+                1,
+                String::new(),
+                "error: \"./src/a.ts\" matches more than one input: [\"file:src/a.ts\", \"src/a.ts\"]\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            run_cli(&["-m", metafile.path().to_str().unwrap(), "render", "./src/a.ts"]).0,
+            1
+        );
+    }
+
+    #[test]
+    fn test_output() {
+        let dynamic = fixture("esbuild-meta-dynamic.json");
+
+        assert_eq!(
+            run_cli(&["-m", &dynamic, "output", "dist/main.js"]),
+            (
+                0,
+                "preloads:\n  dist/chunk-shared.js\nprefetches:\n  dist/chunk-lazy.js\n  dist/lazy.js\n".to_string(),
+                String::new()
+            )
+        );
+
+        let (status, stdout, _) = run_cli(&["-m", &dynamic, "output", "dist/main.js", "--json"]);
+
+        assert_eq!(status, 0);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&stdout).unwrap(),
+            json!({"preloads": ["dist/chunk-shared.js"], "prefetches": ["dist/chunk-lazy.js", "dist/lazy.js"]})
+        );
+        assert_eq!(run_cli(&["-m", &dynamic, "output", "dist/missing.js"]).0, 1);
+    }
+
+    #[test]
+    fn test_render() {
+        let dynamic = fixture("esbuild-meta-dynamic.json");
+
+        assert_eq!(
+            run_cli(&["-m", &dynamic, "render", "src/main.ts"]),
+            (
+                0,
+                [
+                    "<script async src=\"/dist/chunk-shared.js\" type=\"module\"></script>\n",
+                    "<script async src=\"/dist/main.js\" type=\"module\"></script>\n",
+                    "<link rel=\"prefetch\" href=\"/dist/chunk-lazy.js\">\n",
+                    "<link rel=\"prefetch\" href=\"/dist/lazy.js\">\n",
+                ]
+                .concat(),
+                String::new()
+            )
+        );
+
+        let (status, stdout, _) = run_cli(&[
+            "-m",
+            &fixture("esbuild-meta-fonts.json"),
+            "render",
+            "--json",
+            "resources/css/page-common.css",
+        ]);
+        let rendered: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(status, 0);
+        assert_eq!(
+            rendered["assets"],
+            json!([{
+                "path": "static/page-common_DO3RNJ3I.css",
+                "tag": "<link rel=\"stylesheet\" href=\"/static/page-common_DO3RNJ3I.css\">"
+            }])
+        );
+        assert_eq!(rendered["preloads"].as_array().map(Vec::len), Some(3));
+        assert_eq!(
+            run_cli(&["-m", &dynamic, "render", "./src\\main.ts"]),
+            run_cli(&["-m", &dynamic, "render", "src/main.ts"])
+        );
+        assert_eq!(
+            run_cli(&["-m", &dynamic, "render", "src/main.ts", "src/missing.ts"]).0,
+            1
+        );
+    }
+
+    #[test]
+    fn test_render_preloads_file_loader_outputs() {
+        let (status, stdout, _) = run_cli(&[
+            "-m",
+            &fixture("esbuild-meta-svg.json"),
+            "render",
+            "--json",
+            "resources/images/image.svg",
+        ]);
+        let rendered: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(status, 0);
+        assert_eq!(rendered["assets"].as_array().map(Vec::len), Some(1));
+        assert_eq!(rendered["preloads"][0]["path"], "dist/image_123.svg");
+    }
+
+    #[test]
+    fn test_sizes() {
+        let sizes = fixture("esbuild-meta-sizes.json");
+        let (status, stdout, _) = run_cli(&["-m", &sizes, "sizes", "--top", "1"]);
+
+        assert_eq!(status, 0);
+        assert!(
+            stdout.starts_with("src/admin.ts -> dist/admin-EEEE.js\n  own: 7.8 KiB\n"),
+            "{stdout}"
+        );
+
+        let (status, stdout, _) = run_cli(&["-m", &sizes, "--json", "sizes"]);
+        let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(status, 0);
+        assert_eq!(report["entries"][1]["closureBytes"], 55_000);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            run_cli(&["-m", &fixture("esbuild-meta-dynamic.json"), "validate"]),
+            (0, "no problems found\n".to_string(), String::new())
+        );
+
+        let orphan = fixture("esbuild-meta-orphan.json");
+        let (status, stdout, _) = run_cli(&["-m", &orphan, "validate"]);
+
+        assert_eq!(status, 1);
+        assert!(stdout.ends_with("1 problem(s) found\n"), "{stdout}");
+
+        let (status, stdout, _) = run_cli(&["-m", &orphan, "validate", "--json"]);
+        let diagnostics: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+        assert_eq!(status, 1);
+        assert_eq!(diagnostics[0]["kind"], "orphan-output");
+        assert!(diagnostics[0]["outputPath"].is_string());
+    }
+
+    #[test]
+    fn test_unreadable_metafile() {
+        let (status, stdout, stderr) = run_cli(&["-m", &fixture("missing.json"), "entries"]);
+
+        assert_eq!(status, 2);
+        assert_eq!(stdout, "");
+        assert!(
+            stderr.starts_with("error: failed to read esbuild metafile from"),
+            "{stderr}"
+        );
+        assert!(stderr.contains("  caused by: "), "{stderr}");
+    }
+
+    #[test]
+    fn test_usage_errors_and_help() {
+        let (status, stdout, stderr) = run_cli(&["entries"]);
+
+        assert_eq!(status, 2);
+        assert_eq!(stdout, "");
+        assert!(stderr.contains("--metafile"), "{stderr}");
+
+        let (status, stdout, stderr) = run_cli(&["--help"]);
+
+        assert_eq!(status, 0);
+        assert!(stdout.contains("validate"), "{stdout}");
+        assert_eq!(stderr, "");
+    }
+}
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+/// How the closure size of an entry point present in both builds changed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosureSizeChange {
+    pub entry_point: String,
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+    pub delta_bytes: i64,
+}
+
+impl ClosureSizeChange {
+    pub fn new(entry_point: &str, before_bytes: u64, after_bytes: u64) -> Self {
+        Self {
+            entry_point: entry_point.to_string(),
+            before_bytes,
+            after_bytes,
+            delta_bytes: after_bytes as i64 - before_bytes as i64,
+        }
+    }
+}
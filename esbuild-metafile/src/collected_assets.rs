@@ -0,0 +1,69 @@
+use crate::asset::Asset;
+use crate::preloadable_asset::PreloadableAsset;
+use crate::render_context::RenderContext;
+use crate::renders_path::RendersPath;
+use crate::subresource_integrity::SubresourceIntegrity;
+
+/// Deduplicated head tags gathered by an
+/// [`AssetCollector`](crate::asset_collector::AssetCollector).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CollectedAssets {
+    /// Stylesheets first, then scripts, each in dependency order.
+    pub assets: Vec<Asset>,
+    /// Preloads in dependency order, followed by prefetches.
+    pub preloads: Vec<PreloadableAsset>,
+}
+
+impl CollectedAssets {
+    pub fn render<TRendersPath: RendersPath>(&self, renders_path: &TRendersPath) -> String {
+        self.render_with_context(&RenderContext::new(renders_path))
+    }
+
+    pub fn render_with_integrity<TRendersPath: RendersPath>(
+        &self,
+        renders_path: &TRendersPath,
+        integrity: &SubresourceIntegrity,
+    ) -> String {
+        self.render_with_context(&RenderContext::new(renders_path).with_integrity(integrity))
+    }
+
+    pub fn render_with_context<TRendersPath: RendersPath>(
+        &self,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        self.assets
+            .iter()
+            .map(|asset| asset.render_with_context(context))
+            .chain(
+                self.preloads
+                    .iter()
+                    .map(|preload| preload.render_with_context(context)),
+            )
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_renderer::PathRenderer;
+
+    #[test]
+    fn test_render_with_integrity() {
+        let collected = CollectedAssets {
+            assets: vec![
+                Asset::Script("dist/app.js".to_string()),
+                Asset::Unknown("dist/app.bin".to_string()),
+            ],
+            preloads: vec![PreloadableAsset::Module("dist/chunk.js".to_string())],
+        };
+        let integrity = SubresourceIntegrity::default().with_hash("dist/chunk.js", "sha384-chunk");
+
+        assert_eq!(
+            collected.render_with_integrity(&PathRenderer {}, &integrity),
+            "<script async src=\"/dist/app.js\" type=\"module\"></script>\n<link rel=\"modulepreload\" href=\"/dist/chunk.js\" integrity=\"sha384-chunk\">"
+        );
+    }
+}
@@ -0,0 +1,12 @@
+/// What [`MetafileMerger`](crate::metafile_merger::MetafileMerger) does when several
+/// metafiles have the same entry point. Outputs written to the same path by more than one
+/// build always fail the merge, since only one of them can be served.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CollisionPolicy {
+    /// Fail the merge.
+    #[default]
+    Error,
+    /// Prefix colliding entry points with the namespace of their metafile, so they are
+    /// looked up as `admin:src/main.ts`. Output paths are left as they are.
+    Namespace,
+}
@@ -0,0 +1,172 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+
+use crate::asset_type_registry::AssetTypeRegistry;
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+
+fn asset_rank(registry: &AssetTypeRegistry, path: &str) -> u8 {
+    match registry.kind(path) {
+        PreloadableAssetKind::Stylesheet => 0,
+        PreloadableAssetKind::Module => 1,
+        _ => 2,
+    }
+}
+
+/// Orders paths so that every output comes after the outputs it statically imports
+/// (including its `cssBundle`). Among the paths that are ready at the same time,
+/// stylesheets come before modules, modules before other assets, and ties are broken
+/// by path. Paths are classified with the default [`AssetTypeRegistry`]. Import cycles are broken at the lowest ranked path of the cycle.
+pub fn sort_by_dependencies(metafile: &RawEsbuildMetafile, paths: &mut Vec<String>) {
+    let registry = AssetTypeRegistry::default_registry();
+    // nodes are sorted by priority, so a lower index always wins among ready nodes
+    let mut nodes: Vec<(u8, &str)> = paths
+        .iter()
+        .map(|path| (asset_rank(registry, path), path.as_str()))
+        .collect();
+
+    nodes.sort_unstable();
+    nodes.dedup();
+
+    let node_indices: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, (_, path))| (*path, index))
+        .collect();
+    let mut dependency_counts: Vec<usize> = vec![0; nodes.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+
+    for (node, (_, path)) in nodes.iter().enumerate() {
+        if let Some(output) = metafile.outputs.get(*path) {
+            let mut dependencies: Vec<usize> = output
+                .imports
+                .iter()
+                .filter(|import| !import.kind.is_some_and(|kind| kind.is_dynamic()))
+                .map(|import| import.path.as_str())
+                .chain(output.css_bundle.as_deref())
+                .filter_map(|imported_path| node_indices.get(imported_path).copied())
+                .filter(|dependency| *dependency != node)
+                .collect();
+
+            dependencies.sort_unstable();
+            dependencies.dedup();
+            dependency_counts[node] = dependencies.len();
+
+            for dependency in dependencies {
+                dependents[dependency].push(node);
+            }
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..nodes.len())
+        .filter(|node| dependency_counts[*node] == 0)
+        .map(Reverse)
+        .collect();
+    let mut emitted: Vec<bool> = vec![false; nodes.len()];
+    let mut lowest_pending: usize = 0;
+    let mut sorted: Vec<String> = Vec::with_capacity(nodes.len());
+
+    while sorted.len() < nodes.len() {
+        let node = match ready.pop() {
+            Some(Reverse(node)) => node,
+            None => {
+                while emitted[lowest_pending] {
+                    lowest_pending += 1;
+                }
+
+                lowest_pending
+            }
+        };
+
+        if emitted[node] {
+            continue;
+        }
+
+        emitted[node] = true;
+        sorted.push(nodes[node].1.to_string());
+
+        for dependent in &dependents[node] {
+            dependency_counts[*dependent] -= 1;
+
+            if dependency_counts[*dependent] == 0 && !emitted[*dependent] {
+                ready.push(Reverse(*dependent));
+            }
+        }
+    }
+
+    *paths = sorted;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(json: &str, paths: &[&str]) -> Vec<String> {
+        let metafile: RawEsbuildMetafile = serde_json::from_str(json).expect("metafile parses");
+        let mut paths: Vec<String> = paths.iter().map(|path| path.to_string()).collect();
+
+        sort_by_dependencies(&metafile, &mut paths);
+
+        paths
+    }
+
+    #[test]
+    fn test_stylesheets_come_before_modules_and_other_assets() {
+        let paths = sorted(
+            r#"{"outputs": {}}"#,
+            &["dist/b.js", "dist/a.png", "dist/c.mjs", "dist/z.css", "dist/a.js"],
+        );
+
+        assert_eq!(
+            paths,
+            vec!["dist/z.css", "dist/a.js", "dist/b.js", "dist/c.mjs", "dist/a.png"]
+        );
+    }
+
+    #[test]
+    fn test_dependencies_come_before_importers() {
+        let paths = sorted(
+            r#"{"outputs": {
+                "dist/a.js": {"imports": [{"path": "dist/b.js", "kind": "import-statement"}]},
+                "dist/b.js": {"imports": [{"path": "dist/font.woff2", "kind": "url-token"}]},
+                "dist/c.js": {
+                    "imports": [{"path": "dist/a.js", "kind": "dynamic-import"}],
+                    "cssBundle": "dist/c.css"
+                },
+                "dist/c.css": {"imports": [{"path": "dist/c.css"}]}
+            }}"#,
+            &["dist/a.js", "dist/b.js", "dist/c.js", "dist/c.css", "dist/font.woff2"],
+        );
+
+        assert_eq!(
+            paths,
+            vec!["dist/c.css", "dist/c.js", "dist/font.woff2", "dist/b.js", "dist/a.js"]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_paths_are_listed_once() {
+        let paths = sorted(r#"{"outputs": {}}"#, &["dist/a.js", "dist/a.js"]);
+
+        assert_eq!(paths, vec!["dist/a.js"]);
+    }
+
+    #[test]
+    fn test_cycles_are_broken_deterministically() {
+        let paths = sorted(
+            r#"{"outputs": {
+                "dist/a.js": {"imports": [{"path": "dist/b.js"}]},
+                "dist/b.js": {"imports": [{"path": "dist/c.js"}]},
+                "dist/c.js": {"imports": [{"path": "dist/a.js"}]},
+                "dist/d.js": {"imports": [{"path": "dist/a.js"}]}
+            }}"#,
+            &["dist/d.js", "dist/c.js", "dist/b.js", "dist/a.js"],
+        );
+
+        assert_eq!(
+            paths,
+            vec!["dist/a.js", "dist/c.js", "dist/b.js", "dist/d.js"]
+        );
+    }
+}
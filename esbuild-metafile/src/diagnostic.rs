@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+/// A problem found in the output graph of an esbuild metafile by
+/// [`RawEsbuildMetafile::validate`](crate::raw_esbuild_metafile::RawEsbuildMetafile::validate).
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum Diagnostic {
+    #[error("entry point \"{entry_point}\" is claimed by multiple outputs: {output_paths:?}")]
+    DuplicateEntryPoint {
+        entry_point: String,
+        output_paths: Vec<String>,
+    },
+    #[error("outputs import each other in a cycle: {output_paths:?}")]
+    ImportCycle {
+        output_paths: Vec<String>,
+    },
+    #[error("output \"{output_path}\" imports \"{import_path}\" that is not an output")]
+    MissingImport {
+        output_path: String,
+        import_path: String,
+    },
+    #[error("output \"{output_path}\" is not reachable from any entry point or input")]
+    OrphanOutput {
+        output_path: String,
+    },
+    #[error("output \"{output_path}\" has a css bundle \"{css_bundle}\" that is not an output")]
+    UnresolvedCssBundle {
+        output_path: String,
+        css_bundle: String,
+    },
+}
@@ -0,0 +1,19 @@
+use serde::Serialize;
+
+use crate::input_contribution::InputContribution;
+
+/// Page weight of an entry point: its own output, plus the stylesheets and chunks its
+/// preloads pull in.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntrySize {
+    pub entry_point: String,
+    pub output_path: String,
+    pub own_bytes: u64,
+    pub stylesheet_bytes: u64,
+    pub chunk_bytes: u64,
+    /// Sum of the entry output, its stylesheets and its chunks.
+    pub closure_bytes: u64,
+    /// Largest contributing source inputs across the closure, biggest first.
+    pub top_inputs: Vec<InputContribution>,
+}
@@ -1,5 +1,137 @@
+use std::path::PathBuf;
+
+use serde_json::error::Category;
+
+use crate::diagnostic::Diagnostic;
+
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
-    #[error("failed to deserialize esbuild metafile")]
-    Deserialize(#[from] serde_json::Error),
+    #[error("\"{input_path}\" matches more than one input: {candidates:?}")]
+    AmbiguousInput {
+        input_path: String,
+        candidates: Vec<String>,
+    },
+    #[error("entry point \"{entry_point}\" is in more than one metafile: {namespaces:?}")]
+    EntryPointCollision {
+        entry_point: String,
+        namespaces: Vec<String>,
+    },
+    #[error("failed to read esbuild metafile from \"{}\"", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("\"{input_path}\" is not an entry point in the esbuild metafile")]
+    InputNotFound {
+        input_path: String,
+    },
+    #[error("output \"{output_path}\" is in more than one metafile: {namespaces:?}")]
+    OutputCollision {
+        output_path: String,
+        namespaces: Vec<String>,
+    },
+    #[error("failed to read build output from \"{}\"", path.display())]
+    OutputRead {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid glob pattern \"{pattern}\"")]
+    Pattern {
+        pattern: String,
+        #[source]
+        source: globset::Error,
+    },
+    #[error("failed to read esbuild metafile")]
+    Read(#[source] std::io::Error),
+    #[error("esbuild metafile does not match the expected schema at \"{path}\"")]
+    Schema {
+        /// The key in the `outputs` section the error was found in, if any.
+        output_key: Option<String>,
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("esbuild metafile is not valid JSON at line {line}, column {column}")]
+    Syntax {
+        line: usize,
+        column: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Vite manifest could not be parsed at line {line}, column {column}")]
+    ViteManifestParse {
+        line: usize,
+        column: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("failed to read Vite manifest from \"{}\"", path.display())]
+    ViteManifestRead {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("esbuild metafile failed validation with {} diagnostic(s)", .0.len())]
+    Validation(Vec<Diagnostic>),
+}
+
+impl Error {
+    pub(crate) fn vite_manifest_parse(source: serde_json::Error) -> Self {
+        Error::ViteManifestParse {
+            line: source.line(),
+            column: source.column(),
+            source,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        match error.classify() {
+            Category::Io => Error::Read(error.into()),
+            Category::Data => Error::Schema {
+                output_key: None,
+                path: String::new(),
+                source: error,
+            },
+            Category::Eof | Category::Syntax => Error::Syntax {
+                line: error.line(),
+                column: error.column(),
+                source: error,
+            },
+        }
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let path = error.path().to_string();
+        let mut segments = error.path().iter();
+        let output_key = match (segments.next(), segments.next()) {
+            (
+                Some(serde_path_to_error::Segment::Map {
+                    key: section,
+                }),
+                Some(serde_path_to_error::Segment::Map {
+                    key,
+                }),
+            ) if section == "outputs" => Some(key.clone()),
+            _ => None,
+        };
+
+        match Error::from(error.into_inner()) {
+            Error::Schema {
+                source,
+                ..
+            } => Error::Schema {
+                output_key,
+                path,
+                source,
+            },
+            other => other,
+        }
+    }
 }
@@ -1,154 +1,270 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
 
+use crate::asset_manifest::AssetManifest;
+use crate::dependency_order::sort_by_dependencies;
 use crate::error::Error;
 use crate::import::Import;
+use crate::import_kind::ImportKind;
 use crate::input_lookup::InputLookup;
+use crate::input_path::normalize_input_path;
+use crate::input_path::strip_input_namespace;
 use crate::input_properties::InputProperties;
 use crate::output::Output;
 use crate::output_lookup::OutputLookup;
 use crate::output_properties::OutputProperties;
+use crate::path_interner::PathInterner;
 use crate::raw_esbuild_metafile::RawEsbuildMetafile;
 
-fn register_preloads_for_output<'preloads>(
-    metafile: &'preloads RawEsbuildMetafile,
-    outputs: &'preloads mut Vec<String>,
-    preloads: &'preloads mut Vec<String>,
-    remaining_outputs: &'preloads mut HashSet<String>,
-    output_path: &'preloads str,
-) {
-    if let Some(output) = metafile.outputs.get(output_path) {
-        remaining_outputs.remove(output_path);
-
-        let output_path_str = output_path.to_string();
-
-        if !outputs.contains(&output_path_str) {
-            outputs.push(output_path_str);
-
-            register_preloads_from_imports(
-                metafile,
-                outputs,
-                preloads,
-                remaining_outputs,
-                &output.imports,
-            );
-        }
-    }
+#[derive(Default)]
+struct OutputClosure<'metafile> {
+    outputs: HashSet<&'metafile str>,
+    prefetches: HashSet<&'metafile str>,
+    preloads: HashSet<&'metafile str>,
 }
 
-fn register_preloads_from_imports<'preloads>(
-    metafile: &'preloads RawEsbuildMetafile,
-    outputs: &'preloads mut Vec<String>,
-    preloads: &'preloads mut Vec<String>,
-    remaining_outputs: &'preloads mut HashSet<String>,
-    imports: &'preloads [Import],
-) {
-    for Import {
-        path,
-    } in imports
+/// Walks everything an entry output needs with explicit work stacks, so neither the size
+/// of the graph nor the depth of chunk chains affects the call stack.
+///
+/// Static imports become preloads, and those that are outputs themselves are added to the
+/// entry's outputs and walked further. Dynamic imports, and everything reachable from
+/// them, become prefetches unless they are needed eagerly anyway.
+fn collect_output_closure<'metafile>(
+    metafile: &'metafile RawEsbuildMetafile,
+    entry_output_path: &'metafile str,
+    entry_output: &'metafile Output,
+) -> OutputClosure<'metafile> {
+    let mut closure = OutputClosure::default();
+    let mut pending_outputs: Vec<&Output> = vec![entry_output];
+    let mut pending_prefetches: Vec<&str> = Vec::new();
+
+    closure.outputs.insert(entry_output_path);
+
+    if let Some((css_bundle_path, css_bundle)) = entry_output
+        .css_bundle
+        .as_ref()
+        .and_then(|css_bundle| metafile.outputs.get_key_value(css_bundle))
+        && closure.outputs.insert(css_bundle_path)
     {
-        if !preloads.contains(path) {
-            remaining_outputs.remove(path);
-            preloads.push(path.clone());
+        pending_outputs.push(css_bundle);
+    }
 
-            register_preloads_for_output(metafile, outputs, preloads, remaining_outputs, path);
+    while let Some(output) = pending_outputs.pop() {
+        for Import {
+            path,
+            kind,
+            ..
+        } in &output.imports
+        {
+            if kind.is_some_and(ImportKind::is_dynamic) {
+                if closure.prefetches.insert(path) {
+                    pending_prefetches.push(path);
+                }
+            } else if closure.preloads.insert(path)
+                && let Some(imported_output) = metafile.outputs.get(path)
+                && closure.outputs.insert(path)
+            {
+                pending_outputs.push(imported_output);
+            }
         }
     }
+
+    while let Some(prefetch_path) = pending_prefetches.pop() {
+        if let Some(output) = metafile.outputs.get(prefetch_path) {
+            for Import {
+                path,
+                ..
+            } in &output.imports
+            {
+                if closure.prefetches.insert(path) {
+                    pending_prefetches.push(path);
+                }
+            }
+        }
+    }
+
+    closure
+        .prefetches
+        .retain(|path| !closure.preloads.contains(path) && !closure.outputs.contains(path));
+
+    closure
+}
+
+fn to_owned_paths(paths: HashSet<&str>) -> Vec<String> {
+    paths.into_iter().map(str::to_string).collect()
 }
 
+/// Query-optimized index over an esbuild metafile. Every path is interned once, and
+/// lookups hand out reference-counted views into the index instead of copying paths.
 #[derive(Debug, Default)]
 pub struct EsbuildMetafile {
-    input_to_outputs: HashMap<String, Vec<String>>,
-    output_paths: HashSet<String>,
-    output_to_preloads: HashMap<String, Vec<String>>,
-    static_paths: HashMap<String, Vec<String>>,
+    aliases: HashMap<String, String>,
+    entry_outputs: HashMap<Arc<str>, Arc<str>>,
+    inputs: HashMap<Arc<str>, InputProperties>,
+    /// Input paths that are not in normalized form themselves, by their normalized form.
+    normalized_inputs: HashMap<String, Vec<Arc<str>>>,
+    outputs: HashMap<Arc<str>, OutputProperties>,
 }
 
 impl EsbuildMetafile {
+    /// Lets [`resolve_input`](Self::resolve_input) find `input_path` by a short name, like
+    /// `app`. Aliases belong to this index, pass them to
+    /// [`ReloadableMetafile::from_path_with_aliases`](crate::reloadable_metafile::ReloadableMetafile::from_path_with_aliases)
+    /// to keep them across reloads.
+    pub fn with_alias(mut self, alias: &str, input_path: &str) -> Self {
+        self.aliases
+            .insert(alias.to_string(), input_path.to_string());
+        self
+    }
+
     pub fn input(&self, input_path: &str) -> InputLookup {
-        let outputs = self.input_to_outputs.get(input_path).cloned();
-        let static_paths = self.static_paths.get(input_path).cloned();
-
-        match (outputs, static_paths) {
-            (None, None) => InputLookup::NotFound,
-            (outputs, static_paths) => InputLookup::Found(InputProperties {
-                outputs: outputs.unwrap_or_default(),
-                static_paths: static_paths.unwrap_or_default(),
-            }),
+        match self.inputs.get(input_path) {
+            Some(input) => InputLookup::Found(input.clone()),
+            None => InputLookup::NotFound,
         }
     }
 
     pub fn output(&self, output_path: &str) -> OutputLookup {
-        if self.output_paths.contains(output_path) {
-            OutputLookup::Found(OutputProperties {
-                preloads: self
-                    .output_to_preloads
-                    .get(output_path)
-                    .cloned()
-                    .unwrap_or_default(),
-            })
-        } else {
-            OutputLookup::NotFound
+        match self.outputs.get(output_path) {
+            Some(output) => OutputLookup::Found(output.clone()),
+            None => OutputLookup::NotFound,
         }
     }
 
+    /// Looks an input up the way people write paths: aliases are expanded first, then an
+    /// exact match wins, and otherwise the path is compared in its normalized form (see
+    /// [`normalize_input_path`]), also against namespaced entry points without their
+    /// namespace. Paths that match more than one input are an error.
+    pub fn resolve_input(&self, input_path: &str) -> Result<InputLookup, Error> {
+        AssetManifest::resolve_input(self, input_path)
+    }
+
+    /// The input path as it is in the metafile, resolved like in
+    /// [`EsbuildMetafile::resolve_input`].
+    pub fn resolve_input_path(&self, input_path: &str) -> Result<Option<Arc<str>>, Error> {
+        let target_path = self
+            .aliases
+            .get(input_path)
+            .map_or(input_path, String::as_str);
+
+        if let Some((resolved, _)) = self.inputs.get_key_value(target_path) {
+            return Ok(Some(resolved.clone()));
+        }
+
+        let normalized = normalize_input_path(target_path);
+        let mut candidates: Vec<&Arc<str>> = self
+            .inputs
+            .get_key_value(normalized.as_str())
+            .map(|(candidate, _)| candidate)
+            .into_iter()
+            .chain(
+                self.normalized_inputs
+                    .get(&normalized)
+                    .into_iter()
+                    .flatten(),
+            )
+            .collect();
+
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [candidate] => Ok(Some(Arc::clone(candidate))),
+            _ => Err(Error::AmbiguousInput {
+                input_path: input_path.to_string(),
+                candidates: candidates
+                    .into_iter()
+                    .map(|candidate| candidate.to_string())
+                    .collect(),
+            }),
+        }
+    }
+
+    /// The output esbuild generated for an entry point. If several outputs claim the same
+    /// entry point, the one with the lowest path is used.
+    pub fn entry_output(&self, input_path: &str) -> Option<Arc<str>> {
+        self.entry_outputs.get(input_path).cloned()
+    }
+
+    /// Entry point input paths with their entry outputs, sorted by input path.
+    pub fn entry_points(&self) -> Vec<(&str, &str)> {
+        let mut entry_points: Vec<(&str, &str)> = self
+            .entry_outputs
+            .iter()
+            .map(|(input_path, output_path)| (input_path.as_ref(), output_path.as_ref()))
+            .collect();
+
+        entry_points.sort_unstable();
+        entry_points
+    }
+
     pub fn get_output_paths(&self) -> HashSet<String> {
-        self.output_paths.clone()
+        self.outputs.keys().map(|key| key.to_string()).collect()
+    }
+
+    /// Builds the lookup index only if [`RawEsbuildMetafile::validate`] finds no problems.
+    pub fn from_raw_strict(metafile: RawEsbuildMetafile) -> Result<EsbuildMetafile, Error> {
+        let diagnostics = metafile.validate();
+
+        if diagnostics.is_empty() {
+            Ok(metafile.into())
+        } else {
+            Err(Error::Validation(diagnostics))
+        }
+    }
+
+    pub fn from_str_strict(json: &str) -> Result<EsbuildMetafile, Error> {
+        Self::from_raw_strict(RawEsbuildMetafile::from_str(json)?)
+    }
+
+    pub fn from_path<TPath: AsRef<Path>>(path: TPath) -> Result<EsbuildMetafile, Error> {
+        Ok(RawEsbuildMetafile::from_path(path)?.into())
+    }
+
+    pub fn from_reader<TRead: Read>(reader: TRead) -> Result<EsbuildMetafile, Error> {
+        Ok(RawEsbuildMetafile::from_reader(reader)?.into())
     }
 }
 
 impl From<RawEsbuildMetafile> for EsbuildMetafile {
     fn from(metafile: RawEsbuildMetafile) -> EsbuildMetafile {
-        let mut input_to_outputs: HashMap<String, Vec<String>> = HashMap::new();
+        Self::from(&metafile)
+    }
+}
+
+impl From<&RawEsbuildMetafile> for EsbuildMetafile {
+    fn from(metafile: &RawEsbuildMetafile) -> EsbuildMetafile {
+        let mut entry_output_paths: HashMap<&str, &str> = HashMap::new();
+        let mut input_to_output_sets: HashMap<&str, HashSet<&str>> = HashMap::new();
+        let mut output_to_prefetches: HashMap<String, Vec<String>> = HashMap::new();
         let mut output_to_preloads: HashMap<String, Vec<String>> = HashMap::new();
         let mut static_paths: HashMap<String, Vec<String>> = HashMap::new();
 
-        let mut remaining_outputs: HashSet<String> = metafile
-            .outputs
-            .keys()
-            .filter(|path| !path.ends_with(".map"))
-            .cloned()
-            .collect();
-
-        for (
-            output_path,
-            Output {
-                imports,
-                css_bundle,
-                entry_point,
-                inputs,
-            },
-        ) in &metafile.outputs
-        {
-            if let Some(entry_point) = &entry_point {
-                remaining_outputs.remove(output_path);
-
-                let outputs = input_to_outputs.entry(entry_point.clone()).or_default();
-                let preloads = output_to_preloads.entry(output_path.clone()).or_default();
-
-                outputs.push(output_path.clone());
-
-                if let Some(css_bundle) = css_bundle {
-                    register_preloads_for_output(
-                        &metafile,
-                        outputs,
-                        preloads,
-                        &mut remaining_outputs,
-                        css_bundle,
-                    );
-                }
-
-                register_preloads_from_imports(
-                    &metafile,
-                    outputs,
-                    preloads,
-                    &mut remaining_outputs,
-                    imports,
-                );
+        for (output_path, output) in &metafile.outputs {
+            if let Some(entry_point) = &output.entry_point {
+                let closure = collect_output_closure(metafile, output_path, output);
+
+                entry_output_paths
+                    .entry(entry_point)
+                    .and_modify(|entry_output_path| {
+                        *entry_output_path = (*entry_output_path).min(output_path)
+                    })
+                    .or_insert(output_path);
+
+                input_to_output_sets
+                    .entry(entry_point)
+                    .or_default()
+                    .extend(&closure.outputs);
+                output_to_preloads.insert(output_path.clone(), to_owned_paths(closure.preloads));
+                output_to_prefetches
+                    .insert(output_path.clone(), to_owned_paths(closure.prefetches));
             } else {
-                for input_path in inputs.keys() {
-                    remaining_outputs.remove(output_path);
+                for input_path in output.inputs.keys() {
                     static_paths
                         .entry(input_path.to_string())
                         .or_default()
@@ -157,41 +273,160 @@ impl From<RawEsbuildMetafile> for EsbuildMetafile {
             }
         }
 
-        if !remaining_outputs.is_empty() {
-            log::warn!("Some outputs were not processed: {remaining_outputs:?}");
+        let mut input_to_outputs: HashMap<String, Vec<String>> = input_to_output_sets
+            .into_iter()
+            .map(|(input_path, outputs)| (input_path.to_string(), to_owned_paths(outputs)))
+            .collect();
+
+        for paths in input_to_outputs
+            .values_mut()
+            .chain(output_to_prefetches.values_mut())
+            .chain(output_to_preloads.values_mut())
+        {
+            sort_by_dependencies(metafile, paths);
+        }
+
+        for paths in static_paths.values_mut() {
+            paths.sort();
+        }
+
+        let mut interner = PathInterner::default();
+        let no_paths: Arc<[Arc<str>]> = Arc::from([]);
+        let mut inputs: HashMap<Arc<str>, InputProperties> = HashMap::new();
+
+        for input_path in input_to_outputs.keys().chain(static_paths.keys()) {
+            if !inputs.contains_key(input_path.as_str()) {
+                inputs.insert(
+                    interner.intern(input_path),
+                    InputProperties {
+                        outputs: input_to_outputs
+                            .get(input_path)
+                            .map_or_else(|| no_paths.clone(), |paths| interner.intern_all(paths)),
+                        static_paths: static_paths
+                            .get(input_path)
+                            .map_or_else(|| no_paths.clone(), |paths| interner.intern_all(paths)),
+                    },
+                );
+            }
+        }
+
+        let outputs: HashMap<Arc<str>, OutputProperties> = metafile
+            .outputs
+            .keys()
+            .map(|output_path| {
+                (
+                    interner.intern(output_path),
+                    OutputProperties {
+                        prefetches: output_to_prefetches
+                            .get(output_path)
+                            .map_or_else(|| no_paths.clone(), |paths| interner.intern_all(paths)),
+                        preloads: output_to_preloads
+                            .get(output_path)
+                            .map_or_else(|| no_paths.clone(), |paths| interner.intern_all(paths)),
+                    },
+                )
+            })
+            .collect();
+
+        let mut normalized_inputs: HashMap<String, Vec<Arc<str>>> = HashMap::new();
+
+        for input_path in inputs.keys() {
+            let normalized = normalize_input_path(input_path);
+
+            if normalized != input_path.as_ref() {
+                normalized_inputs
+                    .entry(normalized)
+                    .or_default()
+                    .push(input_path.clone());
+            }
+        }
+
+        // namespaced entry points can't be told apart without their namespace
+        for input_path in entry_output_paths.keys() {
+            if let Some(path) = strip_input_namespace(input_path)
+                && let Some((input_path, _)) = inputs.get_key_value(*input_path)
+            {
+                let normalized = normalize_input_path(path);
+
+                if normalized != normalize_input_path(input_path) {
+                    normalized_inputs
+                        .entry(normalized)
+                        .or_default()
+                        .push(input_path.clone());
+                }
+            }
         }
 
+        let entry_outputs = entry_output_paths
+            .into_iter()
+            .map(|(input_path, output_path)| {
+                (interner.intern(input_path), interner.intern(output_path))
+            })
+            .collect();
+
         Self {
-            input_to_outputs,
-            output_paths: metafile
-                .outputs
-                .keys()
-                .map(|key| key.to_string())
-                .collect::<HashSet<String>>(),
-            output_to_preloads,
-            static_paths,
+            aliases: HashMap::new(),
+            entry_outputs,
+            inputs,
+            normalized_inputs,
+            outputs,
         }
     }
 }
 
+impl AssetManifest for EsbuildMetafile {
+    fn input(&self, input_path: &str) -> InputLookup {
+        EsbuildMetafile::input(self, input_path)
+    }
+
+    fn output(&self, output_path: &str) -> OutputLookup {
+        EsbuildMetafile::output(self, output_path)
+    }
+
+    fn entry_output(&self, input_path: &str) -> Option<Arc<str>> {
+        EsbuildMetafile::entry_output(self, input_path)
+    }
+
+    fn resolve_input_path(&self, input_path: &str) -> Result<Option<Arc<str>>, Error> {
+        EsbuildMetafile::resolve_input_path(self, input_path)
+    }
+}
+
 impl FromStr for EsbuildMetafile {
     type Err = Error;
 
     fn from_str(json: &str) -> Result<EsbuildMetafile, Error> {
-        Ok(serde_json::from_str::<RawEsbuildMetafile>(json)?.into())
+        Ok(RawEsbuildMetafile::from_str(json)?.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::Write;
+
     use super::*;
+    use crate::diagnostic::Diagnostic;
+    use crate::test::ESBUILD_CONTENTS_BASIC;
+    use crate::test::ESBUILD_CONTENTS_DEDUP;
+    use crate::test::ESBUILD_CONTENTS_DYNAMIC;
+    use crate::test::ESBUILD_CONTENTS_FONTS;
+    use crate::test::ESBUILD_CONTENTS_GLB;
+    use crate::test::ESBUILD_CONTENTS_ORPHAN;
+    use crate::test::ESBUILD_CONTENTS_SIZES;
+    use crate::test::ESBUILD_CONTENTS_SIZES_NEXT;
+    use crate::test::ESBUILD_CONTENTS_SVG;
     use crate::test::get_metafile_basic;
     use crate::test::get_metafile_dedup;
+    use crate::test::get_metafile_dynamic;
     use crate::test::get_metafile_fonts;
     use crate::test::get_metafile_glb;
     use crate::test::get_metafile_orphan;
     use crate::test::get_metafile_svg;
 
+    fn paths(paths: &[Arc<str>]) -> Vec<&str> {
+        paths.iter().map(|path| path.as_ref()).collect()
+    }
+
     fn found_input(lookup: InputLookup) -> Option<InputProperties> {
         match lookup {
             InputLookup::Found(input) => Some(input),
@@ -206,6 +441,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_entry_points() {
+        let metafile = get_metafile_fonts();
+
+        assert_eq!(
+            metafile.entry_points(),
+            vec![
+                (
+                    "resources/css/page-common.css",
+                    "static/page-common_DO3RNJ3I.css"
+                ),
+                (
+                    "resources/ts/controller_foo.tsx",
+                    "static/controller_foo_CTJMZK66.js"
+                ),
+            ]
+        );
+        assert_eq!(
+            metafile
+                .entry_output("resources/ts/controller_foo.tsx")
+                .as_deref(),
+            Some("static/controller_foo_CTJMZK66.js")
+        );
+        assert_eq!(metafile.entry_output("resources/ts/missing.tsx"), None);
+    }
+
+    #[test]
+    fn test_duplicate_entry_point_uses_lowest_output_path() {
+        let metafile: EsbuildMetafile = r#"{"outputs": {
+            "dist/b.js": {"imports": [], "entryPoint": "src/a.ts", "inputs": {}},
+            "dist/a.js": {"imports": [], "entryPoint": "src/a.ts", "inputs": {}}
+        }}"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(metafile.entry_points(), vec![("src/a.ts", "dist/a.js")]);
+    }
+
     #[test]
     fn test_get_output_paths() {
         let metafile = get_metafile_basic();
@@ -226,12 +499,12 @@ mod tests {
         assert!(
             input
                 .outputs
-                .contains(&"static/page-common_DO3RNJ3I.css".to_string())
+                .contains(&Arc::from("static/page-common_DO3RNJ3I.css"))
         );
         assert!(
             input
                 .outputs
-                .contains(&"static/test_6D5OPEBZ.svg".to_string())
+                .contains(&Arc::from("static/test_6D5OPEBZ.svg"))
         );
     }
 
@@ -245,12 +518,12 @@ mod tests {
         assert!(
             input
                 .outputs
-                .contains(&"static/controller_foo_CTJMZK66.js".to_string())
+                .contains(&Arc::from("static/controller_foo_CTJMZK66.js"))
         );
         assert!(
             input
                 .outputs
-                .contains(&"static/controller_foo_CX2Z63ZH.css".to_string())
+                .contains(&Arc::from("static/controller_foo_CX2Z63ZH.css"))
         );
     }
 
@@ -264,27 +537,27 @@ mod tests {
         assert!(
             output
                 .preloads
-                .contains(&"https://fonts/font1.woff2".to_string())
+                .contains(&Arc::from("https://fonts/font1.woff2"))
         );
         assert!(
             output
                 .preloads
-                .contains(&"https://fonts/font3.woff2".to_string())
+                .contains(&Arc::from("https://fonts/font3.woff2"))
         );
         assert!(
             output
                 .preloads
-                .contains(&"static/chunk-EMZKCXNJ.js".to_string())
+                .contains(&Arc::from("static/chunk-EMZKCXNJ.js"))
         );
         assert!(
             output
                 .preloads
-                .contains(&"static/chunk-PI4ZFSEL.js".to_string())
+                .contains(&Arc::from("static/chunk-PI4ZFSEL.js"))
         );
         assert!(
             output
                 .preloads
-                .contains(&"static/logo_XSTJPNLH.png".to_string())
+                .contains(&Arc::from("static/logo_XSTJPNLH.png"))
         );
     }
 
@@ -298,17 +571,17 @@ mod tests {
         assert!(
             output
                 .preloads
-                .contains(&"https://fonts/font1.woff2".to_string())
+                .contains(&Arc::from("https://fonts/font1.woff2"))
         );
         assert!(
             output
                 .preloads
-                .contains(&"https://fonts/font2.woff2".to_string())
+                .contains(&Arc::from("https://fonts/font2.woff2"))
         );
         assert!(
             output
                 .preloads
-                .contains(&"static/test_6D5OPEBZ.svg".to_string())
+                .contains(&Arc::from("static/test_6D5OPEBZ.svg"))
         );
     }
 
@@ -329,16 +602,16 @@ mod tests {
         assert!(
             input
                 .static_paths
-                .contains(&"dist/model_123.glb".to_string())
+                .contains(&Arc::from("dist/model_123.glb"))
         );
 
         let output =
             found_output(metafile.output("dist/main.js")).expect("expected output to be found");
 
         assert_eq!(output.preloads.len(), 3);
-        assert!(output.preloads.contains(&"dist/chunk-ABC.js".to_string()));
-        assert!(output.preloads.contains(&"dist/chunk-DEF.js".to_string()));
-        assert!(output.preloads.contains(&"dist/model_123.glb".to_string()));
+        assert!(output.preloads.contains(&Arc::from("dist/chunk-ABC.js")));
+        assert!(output.preloads.contains(&Arc::from("dist/chunk-DEF.js")));
+        assert!(output.preloads.contains(&Arc::from("dist/model_123.glb")));
     }
 
     #[test]
@@ -351,7 +624,7 @@ mod tests {
         assert!(
             input
                 .static_paths
-                .contains(&"dist/image_123.svg".to_string())
+                .contains(&Arc::from("dist/image_123.svg"))
         );
     }
 
@@ -367,9 +640,19 @@ mod tests {
     fn test_from_str_rejects_invalid_json() {
         let error = EsbuildMetafile::from_str("not valid json").unwrap_err();
 
-        assert!(matches!(error, Error::Deserialize(_)));
-        assert_eq!(error.to_string(), "failed to deserialize esbuild metafile");
+        assert_eq!(
+            error.to_string(),
+            "esbuild metafile is not valid JSON at line 1, column 2"
+        );
         assert!(std::error::Error::source(&error).is_some());
+        assert!(matches!(
+            error,
+            Error::Syntax {
+                line: 1,
+                column: 2,
+                ..
+            }
+        ));
     }
 
     #[test]
@@ -383,10 +666,350 @@ mod tests {
             input
                 .outputs
                 .iter()
-                .filter(|path| *path == "dist/shared.js")
+                .filter(|path| path.as_ref() == "dist/shared.js")
                 .count(),
             1
         );
-        assert!(input.outputs.contains(&"dist/entry.js".to_string()));
+        assert!(input.outputs.contains(&Arc::from("dist/entry.js")));
+    }
+
+    #[test]
+    fn test_dynamic_imports_are_prefetched_instead_of_preloaded() {
+        let metafile = get_metafile_dynamic();
+        let input = found_input(metafile.input("src/main.ts")).expect("expected input to be found");
+        let output =
+            found_output(metafile.output("dist/main.js")).expect("expected output to be found");
+
+        assert_eq!(
+            paths(&input.outputs),
+            vec!["dist/chunk-shared.js", "dist/main.js"]
+        );
+        assert_eq!(paths(&output.preloads), vec!["dist/chunk-shared.js"]);
+        assert_eq!(
+            paths(&output.prefetches),
+            vec!["dist/chunk-lazy.js", "dist/lazy.js"]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_chunk_entry_preloads_its_static_imports() {
+        let metafile = get_metafile_dynamic();
+        let output =
+            found_output(metafile.output("dist/lazy.js")).expect("expected output to be found");
+
+        assert_eq!(
+            paths(&output.preloads),
+            vec!["dist/chunk-lazy.js", "dist/chunk-shared.js"]
+        );
+        assert!(output.prefetches.is_empty());
+    }
+
+    #[test]
+    fn test_outputs_without_dynamic_imports_have_no_prefetches() {
+        let metafile = get_metafile_fonts();
+        let output = found_output(metafile.output("static/controller_foo_CTJMZK66.js"))
+            .expect("expected output to be found");
+
+        assert!(output.prefetches.is_empty());
+    }
+
+    #[test]
+    fn test_lookups_are_ordered_by_type_and_dependencies() {
+        let metafile = get_metafile_fonts();
+        let input = found_input(metafile.input("resources/ts/controller_foo.tsx"))
+            .expect("expected input to be found");
+        let output = found_output(metafile.output("static/controller_foo_CTJMZK66.js"))
+            .expect("expected output to be found");
+
+        assert_eq!(
+            paths(&input.outputs),
+            vec!["static/controller_foo_CX2Z63ZH.css", "static/controller_foo_CTJMZK66.js",]
+        );
+        assert_eq!(
+            paths(&output.preloads),
+            vec![
+                "static/chunk-EMZKCXNJ.js",
+                "static/chunk-PI4ZFSEL.js",
+                "https://fonts/font1.woff2",
+                "https://fonts/font3.woff2",
+                "static/logo_XSTJPNLH.png",
+            ]
+        );
+
+        let input = found_input(metafile.input("resources/css/page-common.css"))
+            .expect("expected input to be found");
+
+        assert_eq!(
+            paths(&input.outputs),
+            vec!["static/test_6D5OPEBZ.svg", "static/page-common_DO3RNJ3I.css",]
+        );
+    }
+
+    #[test]
+    fn test_lookups_do_not_depend_on_hash_seeds() {
+        let fixtures = [
+            ESBUILD_CONTENTS_BASIC,
+            ESBUILD_CONTENTS_DEDUP,
+            ESBUILD_CONTENTS_DYNAMIC,
+            ESBUILD_CONTENTS_FONTS,
+            ESBUILD_CONTENTS_GLB,
+            ESBUILD_CONTENTS_SIZES,
+            ESBUILD_CONTENTS_SIZES_NEXT,
+            ESBUILD_CONTENTS_SVG,
+        ];
+
+        for fixture in fixtures {
+            let raw: RawEsbuildMetafile = serde_json::from_str(fixture).expect("fixture parses");
+            let mut input_paths: Vec<&String> = raw
+                .outputs
+                .values()
+                .filter_map(|output| output.entry_point.as_ref())
+                .chain(raw.outputs.values().flat_map(|output| output.inputs.keys()))
+                .collect();
+            let mut output_paths: Vec<&String> = raw.outputs.keys().collect();
+
+            input_paths.sort();
+            output_paths.sort();
+
+            // every `HashMap` gets freshly seeded hash keys, so each parse
+            // iterates the raw outputs in a different order
+            let lookups: Vec<(Vec<InputLookup>, Vec<OutputLookup>)> = (0..16)
+                .map(|_| {
+                    let metafile = EsbuildMetafile::from_str(fixture).expect("fixture parses");
+
+                    (
+                        input_paths
+                            .iter()
+                            .map(|path| metafile.input(path))
+                            .collect(),
+                        output_paths
+                            .iter()
+                            .map(|path| metafile.output(path))
+                            .collect(),
+                    )
+                })
+                .collect();
+
+            for lookup in &lookups[1..] {
+                assert_eq!(lookup, &lookups[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_str_strict_accepts_valid_metafile() {
+        let metafile =
+            EsbuildMetafile::from_str_strict(ESBUILD_CONTENTS_GLB).expect("glb fixture is valid");
+
+        assert!(found_input(metafile.input("src/main.ts")).is_some());
+    }
+
+    #[test]
+    fn test_from_str_strict_rejects_orphan_outputs() {
+        let error = EsbuildMetafile::from_str_strict(ESBUILD_CONTENTS_ORPHAN).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "esbuild metafile failed validation with 1 diagnostic(s)"
+        );
+        assert!(matches!(
+            error,
+            Error::Validation(diagnostics) if diagnostics == vec![Diagnostic::OrphanOutput {
+                output_path: "dist/orphan.js".to_string(),
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_from_str_strict_rejects_invalid_json() {
+        let error = EsbuildMetafile::from_str_strict("not valid json").unwrap_err();
+
+        assert!(matches!(error, Error::Syntax { .. }));
+    }
+
+    #[test]
+    fn test_from_path_builds_lookup_index() {
+        let mut file = tempfile::NamedTempFile::new().expect("temp file is created");
+
+        file.write_all(ESBUILD_CONTENTS_GLB.as_bytes())
+            .expect("temp file is written");
+
+        let metafile = EsbuildMetafile::from_path(file.path()).expect("metafile is loaded");
+
+        assert!(found_input(metafile.input("src/main.ts")).is_some());
+    }
+
+    #[test]
+    fn test_from_reader_builds_lookup_index() {
+        let metafile = EsbuildMetafile::from_reader(ESBUILD_CONTENTS_GLB.as_bytes())
+            .expect("metafile is loaded");
+
+        assert!(found_output(metafile.output("dist/main.js")).is_some());
+    }
+
+    #[test]
+    fn test_lookups_share_interned_paths() {
+        let metafile = get_metafile_fonts();
+        let first = found_input(metafile.input("resources/ts/controller_foo.tsx"))
+            .expect("expected input to be found");
+        let second = found_input(metafile.input("resources/ts/controller_foo.tsx"))
+            .expect("expected input to be found");
+        let output = found_output(metafile.output("static/controller_foo_CTJMZK66.js"))
+            .expect("expected output to be found");
+        let css_output = found_output(metafile.output("static/page-common_DO3RNJ3I.css"))
+            .expect("expected output to be found");
+
+        assert!(Arc::ptr_eq(&first.outputs, &second.outputs));
+        assert!(Arc::ptr_eq(&output.preloads[2], &css_output.preloads[0]));
+    }
+
+    fn chained_output(imports: Vec<(String, ImportKind)>, entry_point: Option<&str>) -> Output {
+        Output {
+            bytes: 0,
+            imports: imports
+                .into_iter()
+                .map(|(path, kind)| Import {
+                    path,
+                    kind: Some(kind),
+                    external: false,
+                    original: None,
+                    with: HashMap::new(),
+                })
+                .collect(),
+            exports: Vec::new(),
+            css_bundle: None,
+            entry_point: entry_point.map(str::to_string),
+            inputs: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_large_graph_with_deep_chunk_chain() {
+        const CHUNK_COUNT: usize = 49_998;
+
+        let chunk_path = |index: usize| format!("dist/chunk-{index}.js");
+        let mut outputs: HashMap<String, Output> = (0..CHUNK_COUNT)
+            .map(|index| {
+                let imports = if index + 1 < CHUNK_COUNT {
+                    vec![(chunk_path(index + 1), ImportKind::ImportStatement)]
+                } else {
+                    Vec::new()
+                };
+
+                (chunk_path(index), chained_output(imports, None))
+            })
+            .collect();
+
+        outputs.insert(
+            "dist/main.js".to_string(),
+            chained_output(
+                vec![(chunk_path(0), ImportKind::ImportStatement)],
+                Some("src/main.ts"),
+            ),
+        );
+        outputs.insert(
+            "dist/lazy.js".to_string(),
+            chained_output(
+                vec![(chunk_path(0), ImportKind::DynamicImport)],
+                Some("src/lazy.ts"),
+            ),
+        );
+
+        let metafile = EsbuildMetafile::from(RawEsbuildMetafile {
+            inputs: HashMap::new(),
+            outputs,
+        });
+        let input = found_input(metafile.input("src/main.ts")).expect("expected input to be found");
+        let output =
+            found_output(metafile.output("dist/main.js")).expect("expected output to be found");
+        let lazy_output =
+            found_output(metafile.output("dist/lazy.js")).expect("expected output to be found");
+
+        assert_eq!(metafile.get_output_paths().len(), CHUNK_COUNT + 2);
+        assert_eq!(input.outputs.len(), CHUNK_COUNT + 1);
+        assert_eq!(input.outputs[0].as_ref(), chunk_path(CHUNK_COUNT - 1));
+        assert_eq!(input.outputs[CHUNK_COUNT].as_ref(), "dist/main.js");
+        assert_eq!(output.preloads.len(), CHUNK_COUNT);
+        assert_eq!(output.preloads[0].as_ref(), chunk_path(CHUNK_COUNT - 1));
+        assert_eq!(output.preloads[CHUNK_COUNT - 1].as_ref(), chunk_path(0));
+        assert!(output.prefetches.is_empty());
+        assert!(lazy_output.preloads.is_empty());
+        assert_eq!(lazy_output.prefetches.len(), CHUNK_COUNT);
+        assert_eq!(
+            lazy_output.prefetches[0].as_ref(),
+            chunk_path(CHUNK_COUNT - 1)
+        );
+    }
+
+    #[test]
+    fn test_resolve_input_normalizes_paths() {
+        let metafile = get_metafile_fonts();
+        let exact = found_input(metafile.input("resources/ts/controller_foo.tsx"));
+
+        assert!(exact.is_some());
+
+        for input_path in [
+            "resources/ts/controller_foo.tsx",
+            "./resources/ts/controller_foo.tsx",
+            "resources\\ts\\controller_foo.tsx",
+            "file:resources/ts/controller_foo.tsx",
+        ] {
+            assert_eq!(
+                found_input(metafile.resolve_input(input_path).unwrap()),
+                exact,
+                "{input_path}"
+            );
+        }
+
+        assert_eq!(
+            found_input(
+                metafile
+                    .resolve_input("./resources/ts/missing.tsx")
+                    .unwrap()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_input_aliases() {
+        let metafile = EsbuildMetafile::from_str(ESBUILD_CONTENTS_FONTS)
+            .unwrap()
+            .with_alias("app", "./resources/ts/controller_foo.tsx")
+            .with_alias("missing", "resources/ts/missing.tsx");
+
+        assert_eq!(
+            found_input(metafile.resolve_input("app").unwrap()),
+            found_input(metafile.input("resources/ts/controller_foo.tsx"))
+        );
+        assert_eq!(
+            found_input(metafile.resolve_input("missing").unwrap()),
+            None
+        );
+        assert_eq!(found_input(metafile.input("app")), None);
+    }
+
+    #[test]
+    fn test_resolve_input_reports_ambiguous_matches() {
+        let metafile = r#"{"outputs": {
+            "dist/a.js": {"imports": [], "entryPoint": "src/a.ts", "inputs": {}},
+            "dist/b.js": {"imports": [], "entryPoint": "file:src/a.ts", "inputs": {}},
+            "dist/c.js": {"imports": [], "entryPoint": "./src/a.ts", "inputs": {}}
+        }}"#
+        .parse::<EsbuildMetafile>()
+        .unwrap()
+        .with_alias("app", "src\\a.ts");
+
+        assert!(found_input(metafile.resolve_input("src/a.ts").unwrap()).is_some());
+        assert!(matches!(
+            metafile.resolve_input("app"),
+            Err(Error::AmbiguousInput { input_path, .. }) if input_path == "app"
+        ));
+        assert!(matches!(
+            metafile.resolve_input("src\\a.ts"),
+            Err(Error::AmbiguousInput { input_path, candidates })
+                if input_path == "src\\a.ts"
+                    && candidates == vec!["./src/a.ts", "file:src/a.ts", "src/a.ts"]
+        ));
     }
 }
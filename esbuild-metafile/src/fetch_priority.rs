@@ -0,0 +1,35 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchPriority {
+    Auto,
+    High,
+    Low,
+}
+
+impl FetchPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FetchPriority::Auto => "auto",
+            FetchPriority::High => "high",
+            FetchPriority::Low => "low",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_str_matches_serialized_name() {
+        for priority in [FetchPriority::Auto, FetchPriority::High, FetchPriority::Low] {
+            assert_eq!(
+                serde_json::to_string(&priority).unwrap(),
+                format!("\"{}\"", priority.as_str())
+            );
+        }
+    }
+}
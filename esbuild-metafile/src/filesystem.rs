@@ -3,3 +3,16 @@ use std::path::Path;
 pub fn get_file_extension(path: &str) -> Option<&str> {
     Path::new(path).extension().and_then(|ext| ext.to_str())
 }
+
+fn has_scheme(path: &str, scheme: &str) -> bool {
+    path.get(..scheme.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+}
+
+pub fn is_absolute_url(path: &str) -> bool {
+    has_scheme(path, "http://") || has_scheme(path, "https://") || path.starts_with("//")
+}
+
+pub fn is_data_url(path: &str) -> bool {
+    has_scheme(path, "data:")
+}
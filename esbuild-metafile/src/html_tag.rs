@@ -0,0 +1,133 @@
+/// Escapes a value for use inside a double-quoted HTML attribute.
+pub fn escape_attribute_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Minimal writer for a single HTML start tag. Attribute values are always escaped,
+/// attribute and tag names are expected to be trusted constants.
+pub struct HtmlTag {
+    html: String,
+    name: &'static str,
+}
+
+impl HtmlTag {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            html: format!("<{name}"),
+            name,
+        }
+    }
+
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.html.push(' ');
+        self.html.push_str(name);
+        self.html.push_str("=\"");
+        self.html.push_str(&escape_attribute_value(value));
+        self.html.push('"');
+        self
+    }
+
+    pub fn optional_attribute(self, name: &str, value: Option<&str>) -> Self {
+        match value {
+            Some(value) => self.attribute(name, value),
+            None => self,
+        }
+    }
+
+    pub fn boolean_attribute(mut self, name: &str) -> Self {
+        self.html.push(' ');
+        self.html.push_str(name);
+        self
+    }
+
+    pub fn boolean_attribute_if(self, name: &str, condition: bool) -> Self {
+        if condition {
+            self.boolean_attribute(name)
+        } else {
+            self
+        }
+    }
+
+    /// Finishes a void element, such as `<link>`.
+    pub fn finish(mut self) -> String {
+        self.html.push('>');
+        self.html
+    }
+
+    /// Finishes an element with no content, such as an external `<script>`.
+    pub fn finish_with_closing_tag(self) -> String {
+        self.finish_with_content("")
+    }
+
+    /// Finishes an element with raw text content, such as an inline `<script>`. The
+    /// content is written as is, so it must not contain its own closing tag.
+    pub fn finish_with_content(mut self, content: &str) -> String {
+        self.html.push('>');
+        self.html.push_str(content);
+        self.html.push_str("</");
+        self.html.push_str(self.name);
+        self.html.push('>');
+        self.html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_attribute_value() {
+        assert_eq!(
+            escape_attribute_value("a\"b&c<d>e'f"),
+            "a&quot;b&amp;c&lt;d&gt;e&#39;f"
+        );
+        assert_eq!(escape_attribute_value("/dist/app.js"), "/dist/app.js");
+    }
+
+    #[test]
+    fn test_writes_void_element() {
+        assert_eq!(
+            HtmlTag::new("link")
+                .attribute("href", "/a?b=1&c=\"2\"")
+                .boolean_attribute("crossorigin")
+                .finish(),
+            "<link href=\"/a?b=1&amp;c=&quot;2&quot;\" crossorigin>"
+        );
+    }
+
+    #[test]
+    fn test_optional_and_conditional_attributes() {
+        assert_eq!(
+            HtmlTag::new("link")
+                .optional_attribute("integrity", Some("sha384-a"))
+                .optional_attribute("nonce", None)
+                .boolean_attribute_if("crossorigin", true)
+                .boolean_attribute_if("async", false)
+                .finish(),
+            "<link integrity=\"sha384-a\" crossorigin>"
+        );
+    }
+
+    #[test]
+    fn test_writes_element_with_closing_tag() {
+        assert_eq!(
+            HtmlTag::new("script")
+                .attribute("src", "</script>")
+                .finish_with_closing_tag(),
+            "<script src=\"&lt;/script&gt;\"></script>"
+        );
+    }
+}
@@ -1,7 +1,19 @@
+use std::collections::HashMap;
+
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::import_kind::ImportKind;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Import {
     pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ImportKind>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub external: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub with: HashMap<String, String>,
 }
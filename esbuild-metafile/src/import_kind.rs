@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportKind {
+    ComposesFrom,
+    DynamicImport,
+    EntryPoint,
+    FileLoader,
+    ImportRule,
+    ImportStatement,
+    RequireCall,
+    RequireResolve,
+    UrlToken,
+    /// A kind added to esbuild after this crate was written. Treated as a static import.
+    #[serde(other)]
+    Unknown,
+}
+
+impl ImportKind {
+    /// Imports that are only resolved at runtime, on demand, and should not be loaded
+    /// eagerly together with the importing output.
+    pub fn is_dynamic(self) -> bool {
+        matches!(self, ImportKind::DynamicImport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_kinds_are_static() {
+        let kind: ImportKind = serde_json::from_str(r#""import-source""#).unwrap();
+
+        assert_eq!(kind, ImportKind::Unknown);
+        assert!(!kind.is_dynamic());
+        assert!(
+            serde_json::from_str::<ImportKind>(r#""dynamic-import""#)
+                .unwrap()
+                .is_dynamic()
+        );
+    }
+}
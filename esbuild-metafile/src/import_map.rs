@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::asset_type_registry::AssetTypeRegistry;
+use crate::esbuild_metafile::EsbuildMetafile;
+use crate::html_tag::HtmlTag;
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+use crate::render_context::RenderContext;
+use crate::renders_path::RendersPath;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ImportTarget {
+    /// Rendered through the [`RendersPath`] of the render context.
+    Output(String),
+    /// Used as is.
+    Url(String),
+}
+
+#[derive(Serialize)]
+struct ImportMapJson<'map> {
+    imports: BTreeMap<&'map str, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    integrity: BTreeMap<String, &'map str>,
+}
+
+/// Strips the extension from an entry point, so `resources/ts/controller_foo.tsx`
+/// can be imported as `resources/ts/controller_foo`.
+fn entry_point_specifier(input_path: &str) -> &str {
+    match input_path.rsplit_once('.') {
+        Some((specifier, extension)) if !specifier.is_empty() && !extension.contains('/') => {
+            specifier
+        }
+        _ => input_path,
+    }
+}
+
+/// Builds a `<script type="importmap">` that maps stable specifiers to hashed outputs.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ImportMap {
+    imports: BTreeMap<String, ImportTarget>,
+}
+
+impl ImportMap {
+    /// Maps every JavaScript entry point, without its extension, to its entry output.
+    /// Entry points that would share a specifier, like `src/a.ts` and `src/a.tsx`, keep
+    /// their extension.
+    pub fn from_metafile(metafile: &EsbuildMetafile) -> Self {
+        let registry = AssetTypeRegistry::default_registry();
+        let entry_points: Vec<(&str, &str)> = metafile
+            .entry_points()
+            .into_iter()
+            .filter(|(_, output_path)| registry.kind(output_path) == PreloadableAssetKind::Module)
+            .collect();
+        let mut specifier_counts: HashMap<&str, usize> = HashMap::new();
+
+        for (input_path, _) in &entry_points {
+            *specifier_counts
+                .entry(entry_point_specifier(input_path))
+                .or_default() += 1;
+        }
+
+        entry_points
+            .into_iter()
+            .fold(Self::default(), |import_map, (input_path, output_path)| {
+                let specifier = entry_point_specifier(input_path);
+
+                if specifier_counts[specifier] > 1 {
+                    import_map.with_output(input_path, output_path)
+                } else {
+                    import_map.with_output(specifier, output_path)
+                }
+            })
+    }
+
+    /// Maps a specifier to an output path, which is rendered like any other asset path.
+    pub fn with_output(mut self, specifier: &str, output_path: &str) -> Self {
+        self.imports.insert(
+            specifier.to_string(),
+            ImportTarget::Output(output_path.to_string()),
+        );
+        self
+    }
+
+    /// Maps a specifier to a URL that is used as is, for example for externals.
+    pub fn with_url(mut self, specifier: &str, url: &str) -> Self {
+        self.imports
+            .insert(specifier.to_string(), ImportTarget::Url(url.to_string()));
+        self
+    }
+
+    pub fn render<TRendersPath: RendersPath>(&self, renders_path: &TRendersPath) -> String {
+        self.render_with_context(&RenderContext::new(renders_path))
+    }
+
+    /// Adds the context's nonce to the script tag, and the integrity of every target with
+    /// a known hash to the map's `integrity` section.
+    pub fn render_with_context<TRendersPath: RendersPath>(
+        &self,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        HtmlTag::new("script")
+            .attribute("type", "importmap")
+            .optional_attribute("nonce", context.nonce)
+            .finish_with_content(&self.to_json(context))
+    }
+
+    /// The import map JSON, safe to embed in an inline script.
+    pub fn to_json<TRendersPath: RendersPath>(
+        &self,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        let mut import_map = ImportMapJson {
+            imports: BTreeMap::new(),
+            integrity: BTreeMap::new(),
+        };
+
+        for (specifier, target) in &self.imports {
+            let (path, url) = match target {
+                ImportTarget::Output(output_path) => {
+                    (output_path, context.render_path(output_path))
+                }
+                ImportTarget::Url(url) => (url, url.clone()),
+            };
+
+            if let Some(integrity) = context.integrity_of(path) {
+                import_map.integrity.insert(url.clone(), integrity);
+            }
+
+            import_map.imports.insert(specifier, url);
+        }
+
+        serde_json::to_string(&import_map)
+            .expect("import map always serializes")
+            .replace('<', "\\u003c")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::path_renderer::PathRenderer;
+    use crate::subresource_integrity::SubresourceIntegrity;
+    use crate::test::get_metafile_dynamic;
+    use crate::test::get_metafile_fonts;
+
+    #[test]
+    fn test_entry_point_specifier() {
+        assert_eq!(entry_point_specifier("src/a.tsx"), "src/a");
+        assert_eq!(entry_point_specifier("src.d/a"), "src.d/a");
+        assert_eq!(entry_point_specifier(".env"), ".env");
+    }
+
+    #[test]
+    fn test_maps_javascript_entry_points() {
+        assert_eq!(
+            ImportMap::from_metafile(&get_metafile_fonts()).render(&PathRenderer {}),
+            "<script type=\"importmap\">{\"imports\":{\"resources/ts/controller_foo\":\"/static/controller_foo_CTJMZK66.js\"}}</script>"
+        );
+    }
+
+    #[test]
+    fn test_colliding_specifiers_keep_their_extension() {
+        let metafile = EsbuildMetafile::from_str(
+            r#"{"outputs": {
+                "dist/a-ts.js": {"imports": [], "entryPoint": "src/a.ts"},
+                "dist/a-tsx.js": {"imports": [], "entryPoint": "src/a.tsx"},
+                "dist/a.css": {"imports": [], "entryPoint": "src/a.css"},
+                "dist/b.js": {"imports": [], "entryPoint": "src/b.js"}
+            }}"#,
+        )
+        .expect("metafile parses");
+
+        assert_eq!(
+            ImportMap::from_metafile(&metafile).to_json(&RenderContext::new(&PathRenderer {})),
+            [
+                "{\"imports\":{",
+                "\"src/a.ts\":\"/dist/a-ts.js\",",
+                "\"src/a.tsx\":\"/dist/a-tsx.js\",",
+                "\"src/b\":\"/dist/b.js\"}}",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_extra_mappings_nonce_and_integrity() {
+        let integrity = SubresourceIntegrity::default()
+            .with_hash("dist/lazy.js", "sha384-lazy")
+            .with_hash("https://esm.sh/react", "sha384-react");
+        let context = RenderContext::new(&PathRenderer {})
+            .with_nonce("abc")
+            .with_integrity(&integrity);
+        let import_map = ImportMap::from_metafile(&get_metafile_dynamic())
+            .with_url("react", "https://esm.sh/react")
+            .with_url("</script>", "/vendor/x.js");
+
+        assert_eq!(
+            import_map.render_with_context(&context),
+            [
+                "<script type=\"importmap\" nonce=\"abc\">{\"imports\":{",
+                "\"\\u003c/script>\":\"/vendor/x.js\",",
+                "\"react\":\"https://esm.sh/react\",",
+                "\"src/lazy\":\"/dist/lazy.js\",",
+                "\"src/main\":\"/dist/main.js\"},",
+                "\"integrity\":{",
+                "\"/dist/lazy.js\":\"sha384-lazy\",",
+                "\"https://esm.sh/react\":\"sha384-react\"}}",
+                "</script>",
+            ]
+            .concat()
+        );
+    }
+}
@@ -0,0 +1,19 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::import::Import;
+use crate::input_format::InputFormat;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Input {
+    #[serde(default)]
+    pub bytes: u64,
+    #[serde(default)]
+    pub imports: Vec<Import>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<InputFormat>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub with: HashMap<String, String>,
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+/// How many bytes a source input contributes to the outputs of an entry point.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputContribution {
+    pub input_path: String,
+    pub bytes_in_output: u64,
+}
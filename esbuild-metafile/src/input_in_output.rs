@@ -1,5 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct InputInOutput {}
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InputInOutput {
+    #[serde(rename = "bytesInOutput", default)]
+    pub bytes_in_output: u64,
+}
@@ -0,0 +1,72 @@
+/// Canonicalizes an input path the way template authors tend to write it into the form
+/// esbuild uses for metafile keys: the default `file:` namespace is dropped, backslashes
+/// become slashes, and empty and `.` segments are removed. `..` segments are kept, since
+/// resolving them could point at a different file.
+pub fn normalize_input_path(input_path: &str) -> String {
+    let input_path = input_path.strip_prefix("file:").unwrap_or(input_path);
+    let input_path = input_path.replace('\\', "/");
+    let segments: Vec<&str> = input_path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+
+    if input_path.starts_with('/') {
+        format!("/{}", segments.join("/"))
+    } else {
+        segments.join("/")
+    }
+}
+
+/// The path of a namespaced input, like `src/main.ts` for the `admin:src/main.ts` entry
+/// points [`MetafileMerger`](crate::metafile_merger::MetafileMerger) creates. Single
+/// letters before the colon are taken for Windows drives, not namespaces.
+pub fn strip_input_namespace(input_path: &str) -> Option<&str> {
+    let (namespace, path) = input_path.split_once(':')?;
+
+    (namespace.len() > 1 && !namespace.contains(['/', '\\', '.'])).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_input_path() {
+        for input_path in [
+            "resources/ts/controller_foo.tsx",
+            "./resources/ts/controller_foo.tsx",
+            "resources\\ts\\controller_foo.tsx",
+            ".\\resources\\ts\\controller_foo.tsx",
+            "file:resources/ts/controller_foo.tsx",
+            "file:./resources//ts/./controller_foo.tsx",
+        ] {
+            assert_eq!(
+                normalize_input_path(input_path),
+                "resources/ts/controller_foo.tsx",
+                "{input_path}"
+            );
+        }
+
+        assert_eq!(normalize_input_path("/srv/app/main.ts"), "/srv/app/main.ts");
+        assert_eq!(
+            normalize_input_path("../shared/main.ts"),
+            "../shared/main.ts"
+        );
+        assert_eq!(
+            normalize_input_path("admin:src/main.ts"),
+            "admin:src/main.ts"
+        );
+    }
+
+    #[test]
+    fn test_strip_input_namespace() {
+        assert_eq!(
+            strip_input_namespace("admin:src/main.ts"),
+            Some("src/main.ts")
+        );
+        assert_eq!(strip_input_namespace("src/main.ts"), None);
+        assert_eq!(strip_input_namespace("C:\\src\\main.ts"), None);
+        assert_eq!(strip_input_namespace("./a:b.ts"), None);
+        assert_eq!(strip_input_namespace(":src/main.ts"), None);
+    }
+}
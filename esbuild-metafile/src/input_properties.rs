@@ -1,5 +1,7 @@
+use std::sync::Arc;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InputProperties {
-    pub outputs: Vec<String>,
-    pub static_paths: Vec<String>,
+    pub outputs: Arc<[Arc<str>]>,
+    pub static_paths: Arc<[Arc<str>]>,
 }
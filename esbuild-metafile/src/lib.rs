@@ -1,18 +1,65 @@
 pub mod asset;
+pub mod asset_collector;
+pub mod asset_manifest;
+pub mod asset_type;
+pub mod asset_type_registry;
+pub mod budget_evaluation;
+pub mod budget_result;
+mod byte_size;
+pub mod cdn_path_renderer;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod closure_size_change;
+pub mod collected_assets;
+pub mod collision_policy;
+pub mod cross_origin;
+mod dependency_order;
+pub mod diagnostic;
+pub mod entry_size;
 pub mod error;
 pub mod esbuild_metafile;
+pub mod fetch_priority;
 mod filesystem;
+pub mod html_tag;
 pub mod import;
+pub mod import_kind;
+pub mod import_map;
+pub mod input;
+pub mod input_contribution;
+pub mod input_format;
 pub mod input_in_output;
 pub mod input_lookup;
+pub mod input_path;
 pub mod input_properties;
+pub mod link_header;
+pub mod metafile_diff;
+pub mod metafile_merger;
+mod metafile_validator;
 pub mod output;
 pub mod output_lookup;
 pub mod output_properties;
+mod path_interner;
 pub mod path_renderer;
 pub mod preloadable_asset;
+pub mod preloadable_asset_kind;
 pub mod raw_esbuild_metafile;
+pub mod rehashed_output;
+pub mod reloadable_metafile;
+pub mod render_context;
 pub mod renders_path;
+pub mod script_loading;
+pub mod script_options;
+pub mod script_rules;
+pub mod script_type;
+pub mod size_budget;
+pub mod size_budget_kind;
+pub mod size_budgets;
+pub mod size_report;
+pub mod static_asset_manifest;
+pub mod subresource_integrity;
+pub mod vite_asset_manifest;
+pub mod vite_manifest;
+pub mod vite_manifest_chunk;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,398 @@
+use crate::asset::Asset;
+use crate::collected_assets::CollectedAssets;
+use crate::cross_origin::CrossOrigin;
+use crate::preloadable_asset::PreloadableAsset;
+use crate::render_context::RenderContext;
+use crate::renders_path::RendersPath;
+use crate::script_type::ScriptType;
+
+/// Lower values are kept first when the header does not fit into the budget.
+fn hint_priority(hint: &PreloadableAsset) -> u8 {
+    match hint {
+        PreloadableAsset::Stylesheet(_) => 0,
+        PreloadableAsset::Module(_) => 1,
+        PreloadableAsset::Font(..) => 2,
+        PreloadableAsset::Fetch(_) | PreloadableAsset::Json(_) | PreloadableAsset::Wasm(_) => 3,
+        PreloadableAsset::Image(..) => 4,
+        PreloadableAsset::Audio(..) | PreloadableAsset::Video(..) => 5,
+        PreloadableAsset::Prefetch(_) => 6,
+    }
+}
+
+/// Percent-encodes everything that may not appear between the angle brackets of a
+/// link value, or in an HTTP header at all.
+pub fn escape_uri_reference(uri: &str) -> String {
+    let mut escaped = String::with_capacity(uri.len());
+
+    for byte in uri.bytes() {
+        if byte.is_ascii_graphic() && !matches!(byte, b'<' | b'>' | b'"') {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    escaped
+}
+
+/// Quotes a link parameter value, e.g. a MIME type, which may contain characters that
+/// are not allowed in a token.
+fn quote_parameter_value(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+
+    quoted.push('"');
+
+    for byte in value.bytes() {
+        match byte {
+            b'"' | b'\\' => {
+                quoted.push('\\');
+                quoted.push(byte as char);
+            }
+            b' ' => quoted.push(' '),
+            byte if byte.is_ascii_graphic() => quoted.push(byte as char),
+            byte => quoted.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn cross_origin_parameter(cross_origin: Option<CrossOrigin>) -> &'static str {
+    match cross_origin {
+        Some(CrossOrigin::Anonymous) => "; crossorigin",
+        Some(CrossOrigin::UseCredentials) => "; crossorigin=use-credentials",
+        None => "",
+    }
+}
+
+/// Hints are fetched with the same credentials mode as the tags rendered with the same
+/// context, otherwise browsers can't reuse the preloaded response.
+fn render_link_value<TRendersPath: RendersPath>(
+    hint: &PreloadableAsset,
+    context: &RenderContext<TRendersPath>,
+) -> String {
+    let integrity = context.integrity_of(hint.path());
+    let parameters = match hint {
+        PreloadableAsset::Module(path) => {
+            let options = context.script_options(path);
+
+            if options.script_type == ScriptType::Classic {
+                let cross_origin = options
+                    .cross_origin
+                    .or_else(|| integrity.is_some().then_some(CrossOrigin::Anonymous));
+
+                format!(
+                    "rel=preload; as=script{}",
+                    cross_origin_parameter(cross_origin)
+                )
+            } else {
+                format!(
+                    "rel=modulepreload{}",
+                    cross_origin_parameter(options.cross_origin)
+                )
+            }
+        }
+        PreloadableAsset::Audio(..) => "rel=preload; as=audio".to_string(),
+        PreloadableAsset::Fetch(_) | PreloadableAsset::Json(_) | PreloadableAsset::Wasm(_) => {
+            "rel=preload; as=fetch; crossorigin".to_string()
+        }
+        PreloadableAsset::Font(_, mime_type) => match mime_type {
+            Some(mime_type) => format!(
+                "rel=preload; as=font; type={}; crossorigin",
+                quote_parameter_value(mime_type)
+            ),
+            None => "rel=preload; as=font; crossorigin".to_string(),
+        },
+        PreloadableAsset::Image(..) => "rel=preload; as=image".to_string(),
+        PreloadableAsset::Prefetch(_) => "rel=prefetch".to_string(),
+        PreloadableAsset::Stylesheet(_) => format!(
+            "rel=preload; as=style{}",
+            cross_origin_parameter(integrity.is_some().then_some(CrossOrigin::Anonymous))
+        ),
+        PreloadableAsset::Video(..) => "rel=preload; as=video".to_string(),
+    };
+
+    format!(
+        "<{}>; {parameters}",
+        escape_uri_reference(&context.render_path(hint.path()))
+    )
+}
+
+/// Renders [RFC 8288](https://www.rfc-editor.org/rfc/rfc8288) `Link` header values
+/// for the assets of a page, e.g. for a `103 Early Hints` response.
+///
+/// Entry stylesheets and scripts are hinted as style preloads and module preloads,
+/// followed by the collected preloads. Scripts the [`ScriptRules`](crate::script_rules::ScriptRules)
+/// of the render context make classic are hinted as script preloads instead. If a
+/// maximum length is set, the lowest-priority hints are dropped until the header fits.
+#[derive(Clone, Debug, Default)]
+pub struct LinkHeader {
+    max_length: Option<usize>,
+}
+
+impl LinkHeader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the rendered header value to `max_length` bytes.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Returns the individual link values, highest priority first.
+    pub fn links<TRendersPath: RendersPath>(
+        &self,
+        collected: &CollectedAssets,
+        renders_path: &TRendersPath,
+    ) -> Vec<String> {
+        self.links_with_context(collected, &RenderContext::new(renders_path))
+    }
+
+    pub fn links_with_context<TRendersPath: RendersPath>(
+        &self,
+        collected: &CollectedAssets,
+        context: &RenderContext<TRendersPath>,
+    ) -> Vec<String> {
+        let mut hints: Vec<PreloadableAsset> = collected
+            .assets
+            .iter()
+            .filter_map(|asset| match asset {
+                Asset::Stylesheet(path) => Some(PreloadableAsset::Stylesheet(path.clone())),
+                Asset::Script(path) => Some(PreloadableAsset::Module(path.clone())),
+                Asset::Unknown(_) => None,
+            })
+            .chain(collected.preloads.iter().cloned())
+            .collect();
+
+        hints.sort_by_key(hint_priority);
+
+        let mut length = 0;
+        let mut links = Vec::with_capacity(hints.len());
+
+        for hint in &hints {
+            let link = render_link_value(hint, context);
+            let separator_length = if links.is_empty() {
+                0
+            } else {
+                2
+            };
+
+            length += separator_length + link.len();
+
+            if self
+                .max_length
+                .is_some_and(|max_length| length > max_length)
+            {
+                break;
+            }
+
+            links.push(link);
+        }
+
+        links
+    }
+
+    /// Returns the header value, or an empty string if there is nothing to hint.
+    pub fn render<TRendersPath: RendersPath>(
+        &self,
+        collected: &CollectedAssets,
+        renders_path: &TRendersPath,
+    ) -> String {
+        self.links(collected, renders_path).join(", ")
+    }
+
+    pub fn render_with_context<TRendersPath: RendersPath>(
+        &self,
+        collected: &CollectedAssets,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        self.links_with_context(collected, context).join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset_collector::AssetCollector;
+    use crate::path_renderer::PathRenderer;
+    use crate::script_loading::ScriptLoading;
+    use crate::script_options::ScriptOptions;
+    use crate::script_rules::ScriptRules;
+    use crate::subresource_integrity::SubresourceIntegrity;
+    use crate::test::get_metafile_fonts;
+
+    fn collected_foo() -> CollectedAssets {
+        let collector = AssetCollector::new(get_metafile_fonts());
+
+        collector.add("resources/ts/controller_foo.tsx").unwrap();
+        collector.collect()
+    }
+
+    #[test]
+    fn test_render_entry() {
+        assert_eq!(
+            LinkHeader::new().render(&collected_foo(), &PathRenderer {}),
+            [
+                "</static/controller_foo_CX2Z63ZH.css>; rel=preload; as=style, ",
+                "</static/controller_foo_CTJMZK66.js>; rel=modulepreload, ",
+                "</static/chunk-EMZKCXNJ.js>; rel=modulepreload, ",
+                "</static/chunk-PI4ZFSEL.js>; rel=modulepreload, ",
+                "<https://fonts/font1.woff2>; rel=preload; as=font; type=\"font/woff2\"; crossorigin, ",
+                "<https://fonts/font3.woff2>; rel=preload; as=font; type=\"font/woff2\"; crossorigin, ",
+                "</static/logo_XSTJPNLH.png>; rel=preload; as=image",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_classic_scripts_are_preloaded_as_scripts() {
+        let script_rules = ScriptRules::default()
+            .with_pattern(
+                "static/controller_*.js",
+                ScriptOptions {
+                    loading: ScriptLoading::Defer,
+                    script_type: ScriptType::Classic,
+                    ..ScriptOptions::default()
+                },
+            )
+            .unwrap();
+        let context = RenderContext::new(&PathRenderer {}).with_script_rules(&script_rules);
+        let links = LinkHeader::new().links_with_context(&collected_foo(), &context);
+
+        assert_eq!(
+            links[1],
+            "</static/controller_foo_CTJMZK66.js>; rel=preload; as=script"
+        );
+        assert_eq!(links[2], "</static/chunk-EMZKCXNJ.js>; rel=modulepreload");
+        assert_eq!(
+            LinkHeader::new()
+                .with_max_length(links[0].len())
+                .render_with_context(&collected_foo(), &context),
+            links[0]
+        );
+    }
+
+    #[test]
+    fn test_hints_match_the_cors_mode_of_the_tags() {
+        let integrity = SubresourceIntegrity::default()
+            .with_hash("static/controller_foo_CX2Z63ZH.css", "sha384-css")
+            .with_hash("static/controller_foo_CTJMZK66.js", "sha384-js")
+            .with_hash("static/chunk-EMZKCXNJ.js", "sha384-chunk");
+        let script_rules = ScriptRules::default()
+            .with_pattern(
+                "static/controller_*.js",
+                ScriptOptions {
+                    script_type: ScriptType::Classic,
+                    ..ScriptOptions::default()
+                },
+            )
+            .unwrap()
+            .with_pattern(
+                "static/chunk-PI4ZFSEL.js",
+                ScriptOptions {
+                    cross_origin: Some(CrossOrigin::UseCredentials),
+                    ..ScriptOptions::default()
+                },
+            )
+            .unwrap();
+        let context = RenderContext::new(&PathRenderer {})
+            .with_integrity(&integrity)
+            .with_script_rules(&script_rules);
+
+        assert_eq!(
+            LinkHeader::new().links_with_context(&collected_foo(), &context)[..4],
+            [
+                "</static/controller_foo_CX2Z63ZH.css>; rel=preload; as=style; crossorigin",
+                "</static/controller_foo_CTJMZK66.js>; rel=preload; as=script; crossorigin",
+                "</static/chunk-EMZKCXNJ.js>; rel=modulepreload",
+                "</static/chunk-PI4ZFSEL.js>; rel=modulepreload; crossorigin=use-credentials",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hostile_font_types_are_quoted() {
+        let collected = CollectedAssets {
+            assets: Vec::new(),
+            preloads: vec![
+                PreloadableAsset::Font("dist/a.woff2".to_string(), Some("x\"\\\n y".to_string())),
+                PreloadableAsset::Font("dist/b.font".to_string(), None),
+            ],
+        };
+
+        assert_eq!(
+            LinkHeader::new().links(&collected, &PathRenderer {}),
+            vec![
+                "</dist/a.woff2>; rel=preload; as=font; type=\"x\\\"\\\\%0A y\"; crossorigin",
+                "</dist/b.font>; rel=preload; as=font; crossorigin",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_length_drops_lowest_priority_first() {
+        let collected = collected_foo();
+        let all = LinkHeader::new().links(&collected, &PathRenderer {});
+        let without_image = all[..all.len() - 1].join(", ");
+
+        assert_eq!(
+            LinkHeader::new()
+                .with_max_length(without_image.len())
+                .render(&collected, &PathRenderer {}),
+            without_image
+        );
+        assert_eq!(
+            LinkHeader::new()
+                .with_max_length(all[0].len() + 1)
+                .links(&collected, &PathRenderer {}),
+            vec![all[0].clone()]
+        );
+        assert_eq!(
+            LinkHeader::new()
+                .with_max_length(0)
+                .render(&collected, &PathRenderer {}),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_every_kind_of_hint() {
+        let collected = CollectedAssets {
+            assets: vec![Asset::Unknown("dist/a.bin".to_string())],
+            preloads: vec![
+                PreloadableAsset::Prefetch("dist/lazy.js".to_string()),
+                PreloadableAsset::from_path("dist/a.mp4".to_string()),
+                PreloadableAsset::from_path("dist/a.mp3".to_string()),
+                PreloadableAsset::from_path("dist/a.png".to_string()),
+                PreloadableAsset::Wasm("dist/a.wasm".to_string()),
+                PreloadableAsset::Json("dist/a.json".to_string()),
+                PreloadableAsset::Fetch("dist/data".to_string()),
+            ],
+        };
+
+        assert_eq!(
+            LinkHeader::new().links(&collected, &PathRenderer {}),
+            vec![
+                "</dist/a.wasm>; rel=preload; as=fetch; crossorigin",
+                "</dist/a.json>; rel=preload; as=fetch; crossorigin",
+                "</dist/data>; rel=preload; as=fetch; crossorigin",
+                "</dist/a.png>; rel=preload; as=image",
+                "</dist/a.mp4>; rel=preload; as=video",
+                "</dist/a.mp3>; rel=preload; as=audio",
+                "</dist/lazy.js>; rel=prefetch",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hostile_paths_are_percent_encoded() {
+        assert_eq!(
+            escape_uri_reference("dist/a b>, <c\"ü\n.js"),
+            "dist/a%20b%3E,%20%3Cc%22%C3%BC%0A.js"
+        );
+    }
+}
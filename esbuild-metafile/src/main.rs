@@ -0,0 +1,10 @@
+use std::io;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    ExitCode::from(esbuild_metafile::cli::run(
+        std::env::args_os(),
+        &mut io::stdout().lock(),
+        &mut io::stderr().lock(),
+    ))
+}
@@ -0,0 +1,449 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::asset::Asset;
+use crate::byte_size::format_byte_delta;
+use crate::byte_size::format_bytes;
+use crate::closure_size_change::ClosureSizeChange;
+use crate::entry_size::EntrySize;
+use crate::esbuild_metafile::EsbuildMetafile;
+use crate::import_kind::ImportKind;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+use crate::rehashed_output::RehashedOutput;
+use crate::size_report::SizeReport;
+
+/// Input paths bundled into an output, with their source sizes and the bytes they
+/// contribute to the output.
+type InputFingerprint<'metafile> = Vec<(&'metafile str, u64, u64)>;
+
+/// `None` if an input's source size is unknown, e.g. if the metafile has no `inputs`
+/// section, since a changed input can't be told apart from an unchanged one then.
+fn input_fingerprint<'metafile>(
+    metafile: &'metafile RawEsbuildMetafile,
+    output_path: &str,
+) -> Option<InputFingerprint<'metafile>> {
+    let mut fingerprint: InputFingerprint = metafile.outputs[output_path]
+        .inputs
+        .iter()
+        .map(|(input_path, input_in_output)| {
+            let input = metafile.inputs.get(input_path)?;
+
+            Some((
+                input_path.as_str(),
+                input.bytes,
+                input_in_output.bytes_in_output,
+            ))
+        })
+        .collect::<Option<_>>()?;
+
+    fingerprint.sort_unstable();
+
+    Some(fingerprint)
+}
+
+/// Pairs outputs that only exist in one of the builds by the inputs bundled into them.
+fn find_rehashed_outputs(
+    before: &RawEsbuildMetafile,
+    after: &RawEsbuildMetafile,
+) -> Vec<RehashedOutput> {
+    let mut removed_by_fingerprint: HashMap<InputFingerprint, Vec<&str>> = HashMap::new();
+    let mut added_output_paths: Vec<&str> = after
+        .outputs
+        .keys()
+        .filter(|output_path| !before.outputs.contains_key(*output_path))
+        .map(String::as_str)
+        .collect();
+
+    for output_path in before.outputs.keys() {
+        if !after.outputs.contains_key(output_path)
+            && let Some(fingerprint) = input_fingerprint(before, output_path)
+            && !fingerprint.is_empty()
+        {
+            removed_by_fingerprint
+                .entry(fingerprint)
+                .or_default()
+                .push(output_path);
+        }
+    }
+
+    for removed_paths in removed_by_fingerprint.values_mut() {
+        removed_paths.sort_unstable_by(|left, right| right.cmp(left));
+    }
+
+    added_output_paths.sort_unstable();
+    added_output_paths
+        .into_iter()
+        .filter_map(|after_path| {
+            let before_path = removed_by_fingerprint
+                .get_mut(&input_fingerprint(after, after_path)?)?
+                .pop()?;
+
+            Some(RehashedOutput {
+                before_path: before_path.to_string(),
+                after_path: after_path.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Script chunks that are no entry point and are statically imported by more than one
+/// output, with the input paths bundled into them.
+fn shared_chunks(metafile: &RawEsbuildMetafile) -> Vec<(&str, BTreeSet<&str>)> {
+    let mut importer_counts: HashMap<&str, usize> = HashMap::new();
+
+    for output in metafile.outputs.values() {
+        let static_imports: HashSet<&str> = output
+            .imports
+            .iter()
+            .filter(|import| !import.kind.is_some_and(ImportKind::is_dynamic))
+            .map(|import| import.path.as_str())
+            .collect();
+
+        for import_path in static_imports {
+            *importer_counts.entry(import_path).or_default() += 1;
+        }
+    }
+
+    let mut chunks: Vec<(&str, BTreeSet<&str>)> = metafile
+        .outputs
+        .iter()
+        .filter(|(output_path, output)| {
+            output.entry_point.is_none()
+                && importer_counts
+                    .get(output_path.as_str())
+                    .is_some_and(|count| *count > 1)
+                && matches!(Asset::from_path(output_path.to_string()), Asset::Script(_))
+        })
+        .map(|(output_path, output)| {
+            (
+                output_path.as_str(),
+                output.inputs.keys().map(String::as_str).collect(),
+            )
+        })
+        .collect();
+
+    chunks.sort_unstable();
+    chunks
+}
+
+fn write_list(markdown: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(markdown, "\n### {title}\n");
+
+    for item in items {
+        let _ = writeln!(markdown, "- {item}");
+    }
+}
+
+/// What changed between two builds, e.g. the metafile from `main` and the one from a
+/// branch.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetafileDiff {
+    pub added_entries: Vec<String>,
+    pub removed_entries: Vec<String>,
+    /// Closure sizes of the entry points present in both builds, sorted by entry point.
+    pub closure_changes: Vec<ClosureSizeChange>,
+    /// Outputs that were renamed while every input bundled into them kept its path, its
+    /// size and the bytes it contributes to the output.
+    pub rehashed_outputs: Vec<RehashedOutput>,
+    /// Shared chunks bundling a set of inputs no shared chunk of the previous build did.
+    pub new_shared_chunks: Vec<String>,
+}
+
+impl MetafileDiff {
+    pub fn new(
+        before_raw: &RawEsbuildMetafile,
+        before: &EsbuildMetafile,
+        after_raw: &RawEsbuildMetafile,
+        after: &EsbuildMetafile,
+    ) -> Self {
+        let before_report = SizeReport::new(before_raw, before, 0);
+        let after_report = SizeReport::new(after_raw, after, 0);
+        let before_chunk_inputs: HashSet<BTreeSet<&str>> = shared_chunks(before_raw)
+            .into_iter()
+            .map(|(_, input_paths)| input_paths)
+            .collect();
+
+        let before_entries: HashMap<&str, &EntrySize> = before_report
+            .entries
+            .iter()
+            .map(|entry| (entry.entry_point.as_str(), entry))
+            .collect();
+        let after_entry_points: HashSet<&str> = after_report
+            .entries
+            .iter()
+            .map(|entry| entry.entry_point.as_str())
+            .collect();
+
+        Self {
+            added_entries: after_report
+                .entries
+                .iter()
+                .filter(|entry| !before_entries.contains_key(entry.entry_point.as_str()))
+                .map(|entry| entry.entry_point.clone())
+                .collect(),
+            removed_entries: before_report
+                .entries
+                .iter()
+                .filter(|entry| !after_entry_points.contains(entry.entry_point.as_str()))
+                .map(|entry| entry.entry_point.clone())
+                .collect(),
+            closure_changes: after_report
+                .entries
+                .iter()
+                .filter_map(|after_entry| {
+                    let before_entry = before_entries.get(after_entry.entry_point.as_str())?;
+
+                    Some(ClosureSizeChange::new(
+                        &after_entry.entry_point,
+                        before_entry.closure_bytes,
+                        after_entry.closure_bytes,
+                    ))
+                })
+                .collect(),
+            rehashed_outputs: find_rehashed_outputs(before_raw, after_raw),
+            new_shared_chunks: shared_chunks(after_raw)
+                .into_iter()
+                .filter(|(_, input_paths)| !before_chunk_inputs.contains(input_paths))
+                .map(|(output_path, _)| output_path.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn from_raw(before: &RawEsbuildMetafile, after: &RawEsbuildMetafile) -> Self {
+        Self::new(
+            before,
+            &EsbuildMetafile::from(before),
+            after,
+            &EsbuildMetafile::from(after),
+        )
+    }
+
+    /// Renders the diff for a pull request comment. Empty sections are left out.
+    pub fn render_markdown(&self) -> String {
+        let mut markdown = String::from("## Bundle changes\n");
+        let code = |path: &String| format!("`{path}`");
+
+        write_list(
+            &mut markdown,
+            "Entry points added",
+            &self.added_entries.iter().map(code).collect::<Vec<_>>(),
+        );
+        write_list(
+            &mut markdown,
+            "Entry points removed",
+            &self.removed_entries.iter().map(code).collect::<Vec<_>>(),
+        );
+
+        if !self.closure_changes.is_empty() {
+            let _ = writeln!(markdown, "\n### Closure sizes\n");
+            let _ = writeln!(markdown, "| Entry point | Before | After | Change |");
+            let _ = writeln!(markdown, "| --- | ---: | ---: | ---: |");
+
+            for change in &self.closure_changes {
+                let _ = writeln!(
+                    markdown,
+                    "| `{}` | {} | {} | {} |",
+                    change.entry_point,
+                    format_bytes(change.before_bytes),
+                    format_bytes(change.after_bytes),
+                    format_byte_delta(change.delta_bytes)
+                );
+            }
+        }
+
+        write_list(
+            &mut markdown,
+            "Outputs rehashed without input changes",
+            &self
+                .rehashed_outputs
+                .iter()
+                .map(|output| format!("`{}` → `{}`", output.before_path, output.after_path))
+                .collect::<Vec<_>>(),
+        );
+        write_list(
+            &mut markdown,
+            "New shared chunks",
+            &self.new_shared_chunks.iter().map(code).collect::<Vec<_>>(),
+        );
+
+        markdown
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("metafile diff always serializes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::get_raw_metafile_fonts;
+    use crate::test::get_raw_metafile_sizes;
+    use crate::test::get_raw_metafile_sizes_next;
+
+    fn rehashed(before_path: &str, after_path: &str) -> RehashedOutput {
+        RehashedOutput {
+            before_path: before_path.to_string(),
+            after_path: after_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff =
+            MetafileDiff::from_raw(&get_raw_metafile_sizes(), &get_raw_metafile_sizes_next());
+
+        assert_eq!(
+            diff,
+            MetafileDiff {
+                added_entries: vec!["src/settings.ts".to_string()],
+                removed_entries: vec!["src/admin.ts".to_string()],
+                closure_changes: vec![
+                    ClosureSizeChange::new("src/app.ts", 55_000, 61_100),
+                    ClosureSizeChange::new("src/lazy.ts", 130_000, 130_000),
+                ],
+                rehashed_outputs: vec![
+                    rehashed("dist/app-AAAA.css", "dist/app-BBBB.css"),
+                    rehashed("dist/app-AAAA.js", "dist/app-BBBB.js"),
+                ],
+                new_shared_chunks: vec!["dist/chunk-UI.js".to_string()],
+            }
+        );
+        assert_eq!(diff.closure_changes[0].delta_bytes, 6_100);
+    }
+
+    #[test]
+    fn test_identical_builds() {
+        let raw_metafile = get_raw_metafile_fonts();
+        let diff = MetafileDiff::from_raw(&raw_metafile, &raw_metafile);
+
+        assert!(diff.added_entries.is_empty());
+        assert!(diff.removed_entries.is_empty());
+        assert!(diff.rehashed_outputs.is_empty());
+        assert!(diff.new_shared_chunks.is_empty());
+        assert!(
+            diff.closure_changes
+                .iter()
+                .all(|change| change.delta_bytes == 0)
+        );
+    }
+
+    #[test]
+    fn test_changed_inputs_are_not_rehashes() {
+        let before = get_raw_metafile_sizes();
+        let mut after = get_raw_metafile_sizes_next();
+
+        after
+            .inputs
+            .get_mut("src/app.ts")
+            .expect("app input exists")
+            .bytes += 1;
+
+        assert_eq!(
+            MetafileDiff::from_raw(&before, &after).rehashed_outputs,
+            vec![rehashed("dist/app-AAAA.css", "dist/app-BBBB.css")]
+        );
+    }
+
+    #[test]
+    fn test_changed_contributions_are_not_rehashes() {
+        let before = get_raw_metafile_sizes();
+        let mut after = get_raw_metafile_sizes_next();
+
+        after
+            .outputs
+            .get_mut("dist/app-BBBB.js")
+            .and_then(|output| output.inputs.get_mut("src/app.ts"))
+            .expect("app input is in the app output")
+            .bytes_in_output += 1;
+
+        assert_eq!(
+            MetafileDiff::from_raw(&before, &after).rehashed_outputs,
+            vec![rehashed("dist/app-AAAA.css", "dist/app-BBBB.css")]
+        );
+    }
+
+    #[test]
+    fn test_unknown_source_sizes_are_not_rehashes() {
+        let mut before = get_raw_metafile_sizes();
+        let mut after = get_raw_metafile_sizes_next();
+
+        before.inputs.clear();
+        after.inputs.clear();
+
+        assert!(
+            MetafileDiff::from_raw(&before, &after)
+                .rehashed_outputs
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let diff =
+            MetafileDiff::from_raw(&get_raw_metafile_sizes(), &get_raw_metafile_sizes_next());
+
+        assert_eq!(
+            diff.render_markdown(),
+            [
+                "## Bundle changes\n",
+                "\n### Entry points added\n\n",
+                "- `src/settings.ts`\n",
+                "\n### Entry points removed\n\n",
+                "- `src/admin.ts`\n",
+                "\n### Closure sizes\n\n",
+                "| Entry point | Before | After | Change |\n",
+                "| --- | ---: | ---: | ---: |\n",
+                "| `src/app.ts` | 53.7 KiB | 59.7 KiB | +6.0 KiB |\n",
+                "| `src/lazy.ts` | 127.0 KiB | 127.0 KiB | 0 B |\n",
+                "\n### Outputs rehashed without input changes\n\n",
+                "- `dist/app-AAAA.css` → `dist/app-BBBB.css`\n",
+                "- `dist/app-AAAA.js` → `dist/app-BBBB.js`\n",
+                "\n### New shared chunks\n\n",
+                "- `dist/chunk-UI.js`\n",
+            ]
+            .concat()
+        );
+        assert_eq!(
+            MetafileDiff::from_raw(&get_raw_metafile_sizes(), &get_raw_metafile_sizes())
+                .render_markdown()
+                .lines()
+                .next(),
+            Some("## Bundle changes")
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let diff =
+            MetafileDiff::from_raw(&get_raw_metafile_sizes(), &get_raw_metafile_sizes_next());
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json()).unwrap();
+
+        assert_eq!(
+            json["closureChanges"][0],
+            serde_json::json!({
+                "entryPoint": "src/app.ts",
+                "beforeBytes": 55_000,
+                "afterBytes": 61_100,
+                "deltaBytes": 6_100
+            })
+        );
+        assert_eq!(
+            json["rehashedOutputs"][1],
+            serde_json::json!({"beforePath": "dist/app-AAAA.js", "afterPath": "dist/app-BBBB.js"})
+        );
+        assert_eq!(
+            json["newSharedChunks"],
+            serde_json::json!(["dist/chunk-UI.js"])
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use crate::collision_policy::CollisionPolicy;
+use crate::error::Error;
+use crate::esbuild_metafile::EsbuildMetafile;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+
+#[derive(Default)]
+struct Collisions {
+    entry_points: BTreeMap<String, Vec<String>>,
+    outputs: BTreeMap<String, Vec<String>>,
+}
+
+impl Collisions {
+    fn find(metafiles: &[(String, RawEsbuildMetafile)]) -> Self {
+        let mut collisions = Self::default();
+
+        for (namespace, metafile) in metafiles {
+            // Several outputs of one metafile may claim the same entry point.
+            let entry_points: BTreeSet<&String> = metafile
+                .outputs
+                .values()
+                .filter_map(|output| output.entry_point.as_ref())
+                .collect();
+
+            for output_path in metafile.outputs.keys() {
+                collisions
+                    .outputs
+                    .entry(output_path.clone())
+                    .or_default()
+                    .push(namespace.clone());
+            }
+
+            for entry_point in entry_points {
+                collisions
+                    .entry_points
+                    .entry(entry_point.clone())
+                    .or_default()
+                    .push(namespace.clone());
+            }
+        }
+
+        collisions
+            .entry_points
+            .retain(|_, namespaces| namespaces.len() > 1);
+        collisions
+            .outputs
+            .retain(|_, namespaces| namespaces.len() > 1);
+        collisions
+    }
+
+    fn into_result(self) -> Result<(), Error> {
+        if let Some((entry_point, namespaces)) = self.entry_points.into_iter().next() {
+            return Err(Error::EntryPointCollision {
+                entry_point,
+                namespaces,
+            });
+        }
+
+        if let Some((output_path, namespaces)) = self.outputs.into_iter().next() {
+            return Err(Error::OutputCollision {
+                output_path,
+                namespaces,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Only entry points are renamed: output paths are where the files are served from.
+fn namespace_collisions(
+    namespace: &str,
+    mut metafile: RawEsbuildMetafile,
+    collisions: &Collisions,
+) -> RawEsbuildMetafile {
+    for output in metafile.outputs.values_mut() {
+        if let Some(entry_point) = &mut output.entry_point
+            && collisions.entry_points.contains_key(entry_point.as_str())
+        {
+            *entry_point = format!("{namespace}:{entry_point}");
+        }
+    }
+
+    metafile
+}
+
+/// Combines the metafiles of separate esbuild builds, like an admin app and a public site,
+/// into one lookup index.
+///
+/// Source inputs shared by several builds are expected and kept once: the first build
+/// that lists an input wins, and its `bytes` and `imports` are what
+/// [`MetafileDiff`](crate::metafile_diff::MetafileDiff) and validation see, even if later
+/// builds list the same input differently. Entry points and outputs in more than one
+/// metafile are handled according to the [`CollisionPolicy`].
+#[derive(Debug, Default)]
+pub struct MetafileMerger {
+    metafiles: Vec<(String, RawEsbuildMetafile)>,
+    policy: CollisionPolicy,
+}
+
+impl MetafileMerger {
+    pub fn new(policy: CollisionPolicy) -> Self {
+        Self {
+            metafiles: Vec::new(),
+            policy,
+        }
+    }
+
+    /// Adds the metafile of one build. The namespace names it in errors and prefixes its
+    /// colliding entry points, so it should be unique.
+    pub fn with_metafile(mut self, namespace: &str, metafile: RawEsbuildMetafile) -> Self {
+        self.metafiles.push((namespace.to_string(), metafile));
+        self
+    }
+
+    pub fn merge_raw(self) -> Result<RawEsbuildMetafile, Error> {
+        let mut metafiles = self.metafiles;
+
+        if self.policy == CollisionPolicy::Namespace {
+            let collisions = Collisions::find(&metafiles);
+
+            metafiles = metafiles
+                .into_iter()
+                .map(|(namespace, metafile)| {
+                    let metafile = namespace_collisions(&namespace, metafile, &collisions);
+
+                    (namespace, metafile)
+                })
+                .collect();
+        }
+
+        // Namespacing leaves output collisions, and entry point collisions between
+        // metafiles with the same namespace.
+        Collisions::find(&metafiles).into_result()?;
+
+        let mut inputs = HashMap::new();
+        let mut outputs = HashMap::new();
+
+        for (_, metafile) in metafiles {
+            for (input_path, input) in metafile.inputs {
+                inputs.entry(input_path).or_insert(input);
+            }
+
+            outputs.extend(metafile.outputs);
+        }
+
+        Ok(RawEsbuildMetafile {
+            inputs,
+            outputs,
+        })
+    }
+
+    pub fn merge(self) -> Result<EsbuildMetafile, Error> {
+        Ok(self.merge_raw()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::input_lookup::InputLookup;
+    use crate::output_lookup::OutputLookup;
+    use crate::test::get_raw_metafile_dynamic;
+    use crate::test::get_raw_metafile_fonts;
+
+    fn build(outdir: &str) -> RawEsbuildMetafile {
+        RawEsbuildMetafile::from_str(&format!(
+            r#"{{
+                "inputs": {{"src/main.ts": {{"bytes": 10, "imports": []}}}},
+                "outputs": {{
+                    "{outdir}/main.js": {{
+                        "imports": [
+                            {{"path": "{outdir}/chunk.js", "kind": "import-statement"}},
+                            {{"path": "https://cdn/lib.js", "kind": "import-statement", "external": true}}
+                        ],
+                        "entryPoint": "src/main.ts",
+                        "cssBundle": "{outdir}/main.css",
+                        "inputs": {{"src/main.ts": {{"bytesInOutput": 10}}}}
+                    }},
+                    "{outdir}/main.css": {{"imports": [], "inputs": {{}}}},
+                    "{outdir}/chunk.js": {{"imports": [], "inputs": {{}}}}
+                }}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    fn outputs_of(metafile: &EsbuildMetafile, input_path: &str) -> Option<Vec<String>> {
+        match metafile.input(input_path) {
+            InputLookup::Found(input) => Some(
+                input
+                    .outputs
+                    .iter()
+                    .map(|output_path| output_path.to_string())
+                    .collect(),
+            ),
+            InputLookup::NotFound => None,
+        }
+    }
+
+    #[test]
+    fn test_merge_without_collisions() {
+        let metafile = MetafileMerger::new(CollisionPolicy::Error)
+            .with_metafile("fonts", get_raw_metafile_fonts())
+            .with_metafile("dynamic", get_raw_metafile_dynamic())
+            .merge()
+            .unwrap();
+
+        assert_eq!(
+            metafile.entry_output("resources/ts/controller_foo.tsx"),
+            Some(Arc::from("static/controller_foo_CTJMZK66.js"))
+        );
+        assert_eq!(
+            metafile.entry_output("src/main.ts"),
+            Some(Arc::from("dist/main.js"))
+        );
+        assert!(matches!(
+            metafile.output("dist/lazy.js"),
+            OutputLookup::Found(_)
+        ));
+    }
+
+    #[test]
+    fn test_shared_source_inputs_are_kept_once() {
+        let mut admin = build("admin");
+
+        for output in admin.outputs.values_mut() {
+            output.entry_point = None;
+        }
+
+        let merged = MetafileMerger::default()
+            .with_metafile("admin", admin)
+            .with_metafile("public", build("public"))
+            .merge_raw()
+            .unwrap();
+
+        assert_eq!(merged.inputs.len(), 1);
+        assert_eq!(merged.outputs.len(), 6);
+    }
+
+    #[test]
+    fn test_collisions_are_errors() {
+        assert!(matches!(
+            MetafileMerger::default()
+                .with_metafile("admin", build("admin"))
+                .with_metafile("public", build("public"))
+                .with_metafile("widgets", build("widgets"))
+                .merge_raw(),
+            Err(Error::EntryPointCollision { entry_point, namespaces })
+                if entry_point == "src/main.ts" && namespaces == vec!["admin", "public", "widgets"]
+        ));
+
+        let mut public = build("dist");
+
+        for output in public.outputs.values_mut() {
+            output.entry_point = Some("src/public.ts".to_string());
+        }
+
+        let error = MetafileMerger::new(CollisionPolicy::Error)
+            .with_metafile("admin", build("dist"))
+            .with_metafile("public", public)
+            .merge()
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            r#"output "dist/chunk.js" is in more than one metafile: ["admin", "public"]"#
+        );
+    }
+
+    #[test]
+    fn test_namespace_collisions() {
+        let metafile = MetafileMerger::new(CollisionPolicy::Namespace)
+            .with_metafile("admin", build("dist"))
+            .with_metafile("public", build("public"))
+            .merge()
+            .unwrap();
+
+        assert_eq!(outputs_of(&metafile, "src/main.ts"), None);
+        assert!(matches!(
+            metafile.resolve_input("./src/main.ts"),
+            Err(Error::AmbiguousInput { candidates, .. })
+                if candidates == vec!["admin:src/main.ts", "public:src/main.ts"]
+        ));
+        assert!(matches!(
+            metafile.resolve_input("admin:src/main.ts"),
+            Ok(InputLookup::Found(_))
+        ));
+        assert_eq!(
+            outputs_of(&metafile, "admin:src/main.ts"),
+            Some(vec![
+                "dist/main.css".to_string(),
+                "dist/chunk.js".to_string(),
+                "dist/main.js".to_string(),
+            ])
+        );
+        assert_eq!(
+            outputs_of(&metafile, "public:src/main.ts"),
+            Some(vec![
+                "public/main.css".to_string(),
+                "public/chunk.js".to_string(),
+                "public/main.js".to_string(),
+            ])
+        );
+
+        let metafile = MetafileMerger::new(CollisionPolicy::Namespace)
+            .with_metafile("admin", build("admin"))
+            .with_metafile("public", build("public"))
+            .merge_raw()
+            .unwrap();
+        let main = &metafile.outputs["public/main.js"];
+
+        assert_eq!(main.entry_point.as_deref(), Some("public:src/main.ts"));
+        assert_eq!(main.css_bundle.as_deref(), Some("public/main.css"));
+        assert_eq!(
+            main.imports
+                .iter()
+                .map(|import| import.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["public/chunk.js", "https://cdn/lib.js"]
+        );
+        assert_eq!(metafile.outputs.len(), 6);
+        assert!(metafile.validate().is_empty());
+    }
+
+    #[test]
+    fn test_output_collisions_are_not_namespaced() {
+        assert!(matches!(
+            MetafileMerger::new(CollisionPolicy::Namespace)
+                .with_metafile("admin", build("dist"))
+                .with_metafile("public", build("dist"))
+                .merge(),
+            Err(Error::OutputCollision { output_path, namespaces })
+                if output_path == "dist/chunk.js" && namespaces == vec!["admin", "public"]
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_namespaces_still_collide() {
+        assert!(matches!(
+            MetafileMerger::new(CollisionPolicy::Namespace)
+                .with_metafile("site", build("admin"))
+                .with_metafile("site", build("public"))
+                .merge(),
+            Err(Error::EntryPointCollision { entry_point, namespaces })
+                if entry_point == "site:src/main.ts" && namespaces == vec!["site", "site"]
+        ));
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::diagnostic::Diagnostic;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+
+fn find_duplicate_entry_points(metafile: &RawEsbuildMetafile, diagnostics: &mut Vec<Diagnostic>) {
+    let mut entry_point_outputs: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+
+    for (output_path, output) in &metafile.outputs {
+        if let Some(entry_point) = &output.entry_point {
+            entry_point_outputs
+                .entry(entry_point)
+                .or_default()
+                .push(output_path.clone());
+        }
+    }
+
+    for (entry_point, mut output_paths) in entry_point_outputs {
+        if output_paths.len() > 1 {
+            output_paths.sort();
+            diagnostics.push(Diagnostic::DuplicateEntryPoint {
+                entry_point: entry_point.to_string(),
+                output_paths,
+            });
+        }
+    }
+}
+
+fn find_unresolved_references(metafile: &RawEsbuildMetafile, diagnostics: &mut Vec<Diagnostic>) {
+    for (output_path, output) in &metafile.outputs {
+        if let Some(css_bundle) = &output.css_bundle
+            && !metafile.outputs.contains_key(css_bundle)
+        {
+            diagnostics.push(Diagnostic::UnresolvedCssBundle {
+                output_path: output_path.clone(),
+                css_bundle: css_bundle.clone(),
+            });
+        }
+
+        for import in &output.imports {
+            if !import.external && !metafile.outputs.contains_key(&import.path) {
+                diagnostics.push(Diagnostic::MissingImport {
+                    output_path: output_path.clone(),
+                    import_path: import.path.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn find_orphan_outputs(metafile: &RawEsbuildMetafile, diagnostics: &mut Vec<Diagnostic>) {
+    let mut reachable: HashSet<&str> = HashSet::new();
+    let mut pending: Vec<&str> = metafile
+        .outputs
+        .iter()
+        .filter(|(_, output)| output.entry_point.is_some() || !output.inputs.is_empty())
+        .map(|(output_path, _)| output_path.as_str())
+        .collect();
+
+    while let Some(output_path) = pending.pop() {
+        if !reachable.insert(output_path) {
+            continue;
+        }
+
+        if let Some(output) = metafile.outputs.get(output_path) {
+            pending.extend(output.imports.iter().map(|import| import.path.as_str()));
+            pending.extend(output.css_bundle.as_deref());
+        }
+    }
+
+    for output_path in metafile.outputs.keys() {
+        if !output_path.ends_with(".map") && !reachable.contains(output_path.as_str()) {
+            diagnostics.push(Diagnostic::OrphanOutput {
+                output_path: output_path.clone(),
+            });
+        }
+    }
+}
+
+/// Finds the strongly connected components of the static import graph with an
+/// iterative Tarjan traversal, so deep chunk chains cannot overflow the stack.
+fn find_import_cycles(metafile: &RawEsbuildMetafile, diagnostics: &mut Vec<Diagnostic>) {
+    let mut output_paths: Vec<&str> = metafile.outputs.keys().map(String::as_str).collect();
+
+    output_paths.sort();
+
+    let output_indices: HashMap<&str, usize> = output_paths
+        .iter()
+        .enumerate()
+        .map(|(index, output_path)| (*output_path, index))
+        .collect();
+    let edges: Vec<Vec<usize>> = output_paths
+        .iter()
+        .map(|output_path| {
+            let mut targets: Vec<usize> = metafile.outputs[*output_path]
+                .imports
+                .iter()
+                .filter(|import| !import.kind.is_some_and(|kind| kind.is_dynamic()))
+                .filter_map(|import| output_indices.get(import.path.as_str()).copied())
+                .collect();
+
+            targets.sort_unstable();
+            targets.dedup();
+            targets
+        })
+        .collect();
+
+    let mut indices: Vec<Option<usize>> = vec![None; output_paths.len()];
+    let mut lowlinks: Vec<usize> = vec![0; output_paths.len()];
+    let mut on_stack: Vec<bool> = vec![false; output_paths.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut next_index: usize = 0;
+
+    for root in 0..output_paths.len() {
+        if indices[root].is_some() {
+            continue;
+        }
+
+        let mut call_stack: Vec<(usize, usize)> = vec![(root, 0)];
+
+        indices[root] = Some(next_index);
+        lowlinks[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, next_edge)) = call_stack.last_mut() {
+            let node = *node;
+
+            if let Some(&target) = edges[node].get(*next_edge) {
+                *next_edge += 1;
+
+                match indices[target] {
+                    None => {
+                        indices[target] = Some(next_index);
+                        lowlinks[target] = next_index;
+                        next_index += 1;
+                        stack.push(target);
+                        on_stack[target] = true;
+                        call_stack.push((target, 0));
+                    }
+                    Some(target_index) if on_stack[target] => {
+                        lowlinks[node] = lowlinks[node].min(target_index);
+                    }
+                    Some(_) => {}
+                }
+
+                continue;
+            }
+
+            call_stack.pop();
+
+            if let Some((parent, _)) = call_stack.last() {
+                lowlinks[*parent] = lowlinks[*parent].min(lowlinks[node]);
+            }
+
+            if Some(lowlinks[node]) == indices[node] {
+                let mut component: Vec<String> = Vec::new();
+
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(output_paths[member].to_string());
+
+                    if member == node {
+                        break;
+                    }
+                }
+
+                if component.len() > 1 || edges[node].contains(&node) {
+                    component.sort();
+                    diagnostics.push(Diagnostic::ImportCycle {
+                        output_paths: component,
+                    });
+                }
+            }
+        }
+    }
+}
+
+pub fn validate(metafile: &RawEsbuildMetafile) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    find_duplicate_entry_points(metafile, &mut diagnostics);
+    find_unresolved_references(metafile, &mut diagnostics);
+    find_orphan_outputs(metafile, &mut diagnostics);
+    find_import_cycles(metafile, &mut diagnostics);
+
+    diagnostics.sort();
+    diagnostics.dedup();
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::get_raw_metafile_basic;
+    use crate::test::get_raw_metafile_dynamic;
+    use crate::test::get_raw_metafile_glb;
+    use crate::test::get_raw_metafile_invalid;
+    use crate::test::get_raw_metafile_orphan;
+
+    #[test]
+    fn test_valid_metafile_has_no_diagnostics() {
+        assert!(validate(&get_raw_metafile_glb()).is_empty());
+        assert!(validate(&get_raw_metafile_dynamic()).is_empty());
+    }
+
+    #[test]
+    fn test_orphan_output_is_reported() {
+        assert_eq!(
+            validate(&get_raw_metafile_orphan()),
+            vec![Diagnostic::OrphanOutput {
+                output_path: "dist/orphan.js".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_repeated_problems_are_reported_once() {
+        assert_eq!(
+            validate(&get_raw_metafile_basic()),
+            vec![
+                Diagnostic::MissingImport {
+                    output_path: "dist/main.css".to_string(),
+                    import_path: "dist/style1.css".to_string(),
+                },
+                Diagnostic::MissingImport {
+                    output_path: "dist/main.js".to_string(),
+                    import_path: "dist/chunk1.js".to_string(),
+                },
+                Diagnostic::MissingImport {
+                    output_path: "dist/main.js".to_string(),
+                    import_path: "dist/chunk2.js".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_metafile_reports_every_problem() {
+        assert_eq!(
+            validate(&get_raw_metafile_invalid()),
+            vec![
+                Diagnostic::DuplicateEntryPoint {
+                    entry_point: "src/main.ts".to_string(),
+                    output_paths: vec!["dist/main.js".to_string(), "dist/other.js".to_string()],
+                },
+                Diagnostic::ImportCycle {
+                    output_paths: vec![
+                        "dist/chunk-a.js".to_string(),
+                        "dist/chunk-b.js".to_string(),
+                        "dist/chunk-c.js".to_string(),
+                    ],
+                },
+                Diagnostic::ImportCycle {
+                    output_paths: vec!["dist/chunk-self.js".to_string()],
+                },
+                Diagnostic::MissingImport {
+                    output_path: "dist/other.js".to_string(),
+                    import_path: "dist/missing.js".to_string(),
+                },
+                Diagnostic::UnresolvedCssBundle {
+                    output_path: "dist/main.js".to_string(),
+                    css_bundle: "dist/missing.css".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_render_messages() {
+        assert_eq!(
+            Diagnostic::MissingImport {
+                output_path: "dist/main.js".to_string(),
+                import_path: "dist/chunk.js".to_string(),
+            }
+            .to_string(),
+            "output \"dist/main.js\" imports \"dist/chunk.js\" that is not an output"
+        );
+        assert_eq!(
+            Diagnostic::ImportCycle {
+                output_paths: vec!["dist/a.js".to_string(), "dist/b.js".to_string()],
+            }
+            .to_string(),
+            "outputs import each other in a cycle: [\"dist/a.js\", \"dist/b.js\"]"
+        );
+    }
+}
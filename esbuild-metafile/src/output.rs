@@ -6,12 +6,16 @@ use serde::Serialize;
 use crate::import::Import;
 use crate::input_in_output::InputInOutput;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Output {
+    #[serde(default)]
+    pub bytes: u64,
     pub imports: Vec<Import>,
-    #[serde(rename = "cssBundle")]
+    #[serde(default)]
+    pub exports: Vec<String>,
+    #[serde(rename = "cssBundle", skip_serializing_if = "Option::is_none")]
     pub css_bundle: Option<String>,
-    #[serde(rename = "entryPoint")]
+    #[serde(rename = "entryPoint", skip_serializing_if = "Option::is_none")]
     pub entry_point: Option<String>,
     #[serde(default)]
     pub inputs: HashMap<String, InputInOutput>,
@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct OutputProperties {
-    pub preloads: Vec<String>,
+    pub prefetches: Arc<[Arc<str>]>,
+    pub preloads: Arc<[Arc<str>]>,
 }
@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Hands out a single shared allocation per distinct path, so the lookup index can
+/// return paths by bumping reference counts instead of copying strings.
+#[derive(Default)]
+pub struct PathInterner {
+    paths: HashSet<Arc<str>>,
+}
+
+impl PathInterner {
+    pub fn intern(&mut self, path: &str) -> Arc<str> {
+        if let Some(interned) = self.paths.get(path) {
+            return interned.clone();
+        }
+
+        let interned: Arc<str> = Arc::from(path);
+
+        self.paths.insert(interned.clone());
+
+        interned
+    }
+
+    pub fn intern_all<TPath: AsRef<str>>(&mut self, paths: &[TPath]) -> Arc<[Arc<str>]> {
+        paths
+            .iter()
+            .map(|path| self.intern(path.as_ref()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_path_is_interned_once() {
+        let mut interner = PathInterner::default();
+        let first = interner.intern("dist/main.js");
+        let second = interner.intern("dist/main.js");
+        let other = interner.intern("dist/other.js");
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &other));
+    }
+
+    #[test]
+    fn test_lists_share_interned_paths() {
+        let mut interner = PathInterner::default();
+        let first = interner.intern_all(&["dist/a.js".to_string(), "dist/b.js".to_string()]);
+        let second = interner.intern_all(&["dist/b.js".to_string()]);
+
+        assert!(Arc::ptr_eq(&first[1], &second[0]));
+    }
+}
@@ -1,13 +1,41 @@
+use crate::filesystem::is_absolute_url;
+use crate::filesystem::is_data_url;
 use crate::renders_path::RendersPath;
 
 pub struct PathRenderer {}
 
 impl RendersPath for PathRenderer {
     fn render_path(&self, path: &str) -> String {
-        if path.starts_with("http://") || path.starts_with("https://") {
+        if is_absolute_url(path) || is_data_url(path) {
             path.to_string()
         } else {
             format!("/{path}")
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_are_passed_through() {
+        for url in [
+            "http://cdn/a.js",
+            "https://cdn/a.js",
+            "HTTPS://cdn/a.js",
+            "Http://cdn/a.js",
+            "//cdn/a.js",
+            "data:font/woff2;base64,AAAA",
+            "DATA:image/png;base64,AAAA",
+        ] {
+            assert_eq!(PathRenderer {}.render_path(url), url);
+        }
+    }
+
+    #[test]
+    fn test_relative_paths_are_rooted() {
+        assert_eq!(PathRenderer {}.render_path("static/a.js"), "/static/a.js");
+        assert_eq!(PathRenderer {}.render_path("data/a.json"), "/data/a.json");
+    }
+}
@@ -1,51 +1,146 @@
-use crate::filesystem::get_file_extension;
+use crate::asset_type_registry::AssetTypeRegistry;
+use crate::html_tag::HtmlTag;
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+use crate::render_context::RenderContext;
 use crate::renders_path::RendersPath;
+use crate::subresource_integrity::SubresourceIntegrity;
 
-#[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
+fn render_media_preload<TRendersPath: RendersPath>(
+    context: &RenderContext<TRendersPath>,
+    path: &str,
+    mime_type: Option<&str>,
+    destination: &str,
+) -> String {
+    HtmlTag::new("link")
+        .attribute("rel", "preload")
+        .attribute("href", &context.render_path(path))
+        .attribute("as", destination)
+        .optional_attribute("type", mime_type)
+        .finish()
+}
+
+/// A resource preloaded or prefetched ahead of use. Fonts and media carry the MIME type
+/// they were classified with, so the `type` always agrees with the kind.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum PreloadableAsset {
     Fetch(String),
-    Font(String),
-    Image(String),
+    Font(String, Option<String>),
+    Image(String, Option<String>),
     Stylesheet(String),
     Module(String),
+    Prefetch(String),
+    Audio(String, Option<String>),
+    Json(String),
+    Video(String, Option<String>),
+    Wasm(String),
 }
 
 impl PreloadableAsset {
     pub fn from_path(path: String) -> Self {
-        match get_file_extension(&path) {
-            Some("js") => PreloadableAsset::Module(path),
-            Some("css") => PreloadableAsset::Stylesheet(path),
-            Some("woff") | Some("woff2") | Some("ttf") | Some("otf") => {
-                PreloadableAsset::Font(path)
-            }
-            Some("png") | Some("jpg") | Some("jpeg") | Some("gif") | Some("webp")
-            | Some("avif") | Some("svg") => PreloadableAsset::Image(path),
-            _ => PreloadableAsset::Fetch(path),
+        Self::from_path_with_registry(path, AssetTypeRegistry::default_registry())
+    }
+
+    /// Takes both the kind and the MIME type from `registry`.
+    pub fn from_path_with_registry(path: String, registry: &AssetTypeRegistry) -> Self {
+        let mime_type = registry.mime_type(&path).map(str::to_string);
+
+        match registry.kind(&path) {
+            PreloadableAssetKind::Audio => PreloadableAsset::Audio(path, mime_type),
+            PreloadableAssetKind::Fetch => PreloadableAsset::Fetch(path),
+            PreloadableAssetKind::Font => PreloadableAsset::Font(path, mime_type),
+            PreloadableAssetKind::Image => PreloadableAsset::Image(path, mime_type),
+            PreloadableAssetKind::Json => PreloadableAsset::Json(path),
+            PreloadableAssetKind::Module => PreloadableAsset::Module(path),
+            PreloadableAssetKind::Stylesheet => PreloadableAsset::Stylesheet(path),
+            PreloadableAssetKind::Video => PreloadableAsset::Video(path, mime_type),
+            PreloadableAssetKind::Wasm => PreloadableAsset::Wasm(path),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            PreloadableAsset::Audio(path, _)
+            | PreloadableAsset::Fetch(path)
+            | PreloadableAsset::Font(path, _)
+            | PreloadableAsset::Image(path, _)
+            | PreloadableAsset::Stylesheet(path)
+            | PreloadableAsset::Module(path)
+            | PreloadableAsset::Prefetch(path)
+            | PreloadableAsset::Json(path)
+            | PreloadableAsset::Video(path, _)
+            | PreloadableAsset::Wasm(path) => path,
         }
     }
 
     pub fn render<TRendersPath: RendersPath>(&self, renders_path: &TRendersPath) -> String {
+        self.render_with_context(&RenderContext::new(renders_path))
+    }
+
+    /// Adds the `integrity` of the asset, if known, together with the `crossorigin`
+    /// attribute that integrity checks need. Media preloads and prefetches are left
+    /// as they are, since the requests that later use them are not made in CORS mode.
+    pub fn render_with_integrity<TRendersPath: RendersPath>(
+        &self,
+        renders_path: &TRendersPath,
+        integrity: &SubresourceIntegrity,
+    ) -> String {
+        self.render_with_context(&RenderContext::new(renders_path).with_integrity(integrity))
+    }
+
+    /// Module and stylesheet preloads also get the context's nonce, since the
+    /// Content-Security-Policy checks them against `script-src` and `style-src`. Font
+    /// and media preloads get a `type`, so browsers can skip formats they don't support.
+    pub fn render_with_context<TRendersPath: RendersPath>(
+        &self,
+        context: &RenderContext<TRendersPath>,
+    ) -> String {
+        let integrity = context.integrity_of(self.path());
+
         match self {
-            PreloadableAsset::Fetch(path) => format!(
-                "<link rel=\"preload\" href=\"{}\" as=\"fetch\" crossorigin>",
-                renders_path.render_path(path),
-            ),
-            PreloadableAsset::Font(path) => format!(
-                "<link rel=\"preload\" href=\"{}\" as=\"font\" crossorigin>",
-                renders_path.render_path(path),
-            ),
-            PreloadableAsset::Image(path) => format!(
-                "<link rel=\"preload\" href=\"{}\" as=\"image\">",
-                renders_path.render_path(path),
-            ),
-            PreloadableAsset::Module(path) => format!(
-                "<link rel=\"modulepreload\" href=\"{}\">",
-                renders_path.render_path(path),
-            ),
-            PreloadableAsset::Stylesheet(path) => format!(
-                "<link rel=\"preload\" href=\"{}\" as=\"style\">",
-                renders_path.render_path(path),
-            ),
+            PreloadableAsset::Audio(path, mime_type) => {
+                render_media_preload(context, path, mime_type.as_deref(), "audio")
+            }
+            PreloadableAsset::Fetch(path)
+            | PreloadableAsset::Json(path)
+            | PreloadableAsset::Wasm(path) => HtmlTag::new("link")
+                .attribute("rel", "preload")
+                .attribute("href", &context.render_path(path))
+                .attribute("as", "fetch")
+                .optional_attribute("integrity", integrity)
+                .boolean_attribute("crossorigin")
+                .finish(),
+            PreloadableAsset::Font(path, mime_type) => HtmlTag::new("link")
+                .attribute("rel", "preload")
+                .attribute("href", &context.render_path(path))
+                .attribute("as", "font")
+                .optional_attribute("type", mime_type.as_deref())
+                .optional_attribute("integrity", integrity)
+                .boolean_attribute("crossorigin")
+                .finish(),
+            PreloadableAsset::Image(path, mime_type) => {
+                render_media_preload(context, path, mime_type.as_deref(), "image")
+            }
+            PreloadableAsset::Module(path) => HtmlTag::new("link")
+                .attribute("rel", "modulepreload")
+                .attribute("href", &context.render_path(path))
+                .optional_attribute("integrity", integrity)
+                .optional_attribute("nonce", context.nonce)
+                .finish(),
+            PreloadableAsset::Prefetch(path) => HtmlTag::new("link")
+                .attribute("rel", "prefetch")
+                .attribute("href", &context.render_path(path))
+                .finish(),
+            PreloadableAsset::Stylesheet(path) => HtmlTag::new("link")
+                .attribute("rel", "preload")
+                .attribute("href", &context.render_path(path))
+                .attribute("as", "style")
+                .optional_attribute("integrity", integrity)
+                .boolean_attribute_if("crossorigin", integrity.is_some())
+                .optional_attribute("nonce", context.nonce)
+                .finish(),
+            PreloadableAsset::Video(path, mime_type) => {
+                render_media_preload(context, path, mime_type.as_deref(), "video")
+            }
         }
     }
 }
@@ -62,19 +157,20 @@ mod tests {
 
         assert_eq!(
             font,
-            "<link rel=\"preload\" href=\"/fonts/Roboto.woff2\" as=\"font\" crossorigin>"
+            "<link rel=\"preload\" href=\"/fonts/Roboto.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>"
         );
     }
 
     #[test]
     fn test_url_font_formatting() {
-        let font =
-            PreloadableAsset::Font("https://fonts.somewhere.com/fonts/Roboto.woff2".to_string())
-                .render(&PathRenderer {});
+        let font = PreloadableAsset::from_path(
+            "https://fonts.somewhere.com/fonts/Roboto.woff2".to_string(),
+        )
+        .render(&PathRenderer {});
 
         assert_eq!(
             font,
-            "<link rel=\"preload\" href=\"https://fonts.somewhere.com/fonts/Roboto.woff2\" as=\"font\" crossorigin>"
+            "<link rel=\"preload\" href=\"https://fonts.somewhere.com/fonts/Roboto.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>"
         );
     }
 
@@ -99,17 +195,89 @@ mod tests {
 
     #[test]
     fn test_all_image_extensions_render_image_preload() {
-        for extension in ["png", "jpg", "jpeg", "gif", "webp", "avif", "svg"] {
+        for (extension, mime_type) in [
+            ("png", "image/png"),
+            ("jpg", "image/jpeg"),
+            ("jpeg", "image/jpeg"),
+            ("gif", "image/gif"),
+            ("webp", "image/webp"),
+            ("avif", "image/avif"),
+            ("svg", "image/svg+xml"),
+            ("ico", "image/x-icon"),
+        ] {
             let path = format!("dist/logo.{extension}");
             let image = PreloadableAsset::from_path(path.clone()).render(&PathRenderer {});
 
             assert_eq!(
                 image,
-                format!("<link rel=\"preload\" href=\"/{path}\" as=\"image\">")
+                format!(
+                    "<link rel=\"preload\" href=\"/{path}\" as=\"image\" type=\"{mime_type}\">"
+                )
             );
         }
     }
 
+    #[test]
+    fn test_media_wasm_and_json_preloads() {
+        let render =
+            |path: &str| PreloadableAsset::from_path(path.to_string()).render(&PathRenderer {});
+
+        assert_eq!(
+            render("dist/intro.mp4"),
+            "<link rel=\"preload\" href=\"/dist/intro.mp4\" as=\"video\" type=\"video/mp4\">"
+        );
+        assert_eq!(
+            render("dist/click.mp3"),
+            "<link rel=\"preload\" href=\"/dist/click.mp3\" as=\"audio\" type=\"audio/mpeg\">"
+        );
+        assert_eq!(
+            render("dist/app.wasm"),
+            "<link rel=\"preload\" href=\"/dist/app.wasm\" as=\"fetch\" crossorigin>"
+        );
+        assert_eq!(
+            render("dist/data.json"),
+            "<link rel=\"preload\" href=\"/dist/data.json\" as=\"fetch\" crossorigin>"
+        );
+        assert_eq!(
+            PreloadableAsset::from_path("dist/model.glb".to_string()),
+            PreloadableAsset::Fetch("dist/model.glb".to_string())
+        );
+    }
+
+    #[test]
+    fn test_registry_sets_kind_and_type() {
+        let registry = AssetTypeRegistry::default()
+            .with_extension("glb", PreloadableAssetKind::Video, Some("video/x-test"))
+            .with_extension("woff2", PreloadableAssetKind::Font, None);
+        let asset =
+            PreloadableAsset::from_path_with_registry("dist/model.glb".to_string(), &registry);
+
+        assert_eq!(
+            asset,
+            PreloadableAsset::Video(
+                "dist/model.glb".to_string(),
+                Some("video/x-test".to_string())
+            )
+        );
+        assert_eq!(
+            asset.render(&PathRenderer {}),
+            "<link rel=\"preload\" href=\"/dist/model.glb\" as=\"video\" type=\"video/x-test\">"
+        );
+        assert_eq!(
+            PreloadableAsset::from_path_with_registry("dist/a.woff2".to_string(), &registry)
+                .render(&PathRenderer {}),
+            "<link rel=\"preload\" href=\"/dist/a.woff2\" as=\"font\" crossorigin>"
+        );
+    }
+
+    #[test]
+    fn test_prefetch_renders_prefetch_link() {
+        let prefetch =
+            PreloadableAsset::Prefetch("dist/lazy.js".to_string()).render(&PathRenderer {});
+
+        assert_eq!(prefetch, "<link rel=\"prefetch\" href=\"/dist/lazy.js\">");
+    }
+
     #[test]
     fn test_extensionless_path_renders_fetch_preload() {
         let fetch = PreloadableAsset::from_path("dist/data".to_string()).render(&PathRenderer {});
@@ -119,4 +287,130 @@ mod tests {
             "<link rel=\"preload\" href=\"/dist/data\" as=\"fetch\" crossorigin>"
         );
     }
+
+    #[test]
+    fn test_hostile_paths_are_escaped() {
+        let hostile = "https://cdn/a\" onload=\"alert('&')\"<";
+        let escaped = "https://cdn/a&quot; onload=&quot;alert(&#39;&amp;&#39;)&quot;&lt;";
+        let render = |asset: fn(String) -> PreloadableAsset| {
+            asset(hostile.to_string()).render(&PathRenderer {})
+        };
+
+        assert_eq!(
+            render(PreloadableAsset::Fetch),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"fetch\" crossorigin>")
+        );
+        assert_eq!(
+            render(|path| PreloadableAsset::Font(path, None)),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"font\" crossorigin>")
+        );
+        assert_eq!(
+            render(|path| PreloadableAsset::Image(path, None)),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"image\">")
+        );
+        assert_eq!(
+            render(|path| PreloadableAsset::Audio(path, None)),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"audio\">")
+        );
+        assert_eq!(
+            render(|path| PreloadableAsset::Video(path.clone(), Some(path))),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"video\" type=\"{escaped}\">")
+        );
+        assert_eq!(
+            render(PreloadableAsset::Json),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"fetch\" crossorigin>")
+        );
+        assert_eq!(
+            render(PreloadableAsset::Wasm),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"fetch\" crossorigin>")
+        );
+        assert_eq!(
+            render(PreloadableAsset::Module),
+            format!("<link rel=\"modulepreload\" href=\"{escaped}\">")
+        );
+        assert_eq!(
+            render(PreloadableAsset::Prefetch),
+            format!("<link rel=\"prefetch\" href=\"{escaped}\">")
+        );
+        assert_eq!(
+            render(PreloadableAsset::Stylesheet),
+            format!("<link rel=\"preload\" href=\"{escaped}\" as=\"style\">")
+        );
+    }
+
+    #[test]
+    fn test_render_with_integrity() {
+        let integrity = SubresourceIntegrity::default()
+            .with_hash("dist/app.js", "sha384-js")
+            .with_hash("dist/app.css", "sha384-css")
+            .with_hash("dist/data", "sha384-data")
+            .with_hash("https://fonts/a.woff2", "sha384-font")
+            .with_hash("dist/logo.png", "sha384-png");
+        let render = |asset: fn(String) -> PreloadableAsset, path: &str| {
+            asset(path.to_string()).render_with_integrity(&PathRenderer {}, &integrity)
+        };
+
+        assert_eq!(
+            render(PreloadableAsset::Module, "dist/app.js"),
+            "<link rel=\"modulepreload\" href=\"/dist/app.js\" integrity=\"sha384-js\">"
+        );
+        assert_eq!(
+            render(PreloadableAsset::Stylesheet, "dist/app.css"),
+            "<link rel=\"preload\" href=\"/dist/app.css\" as=\"style\" integrity=\"sha384-css\" crossorigin>"
+        );
+        assert_eq!(
+            render(PreloadableAsset::Fetch, "dist/data"),
+            "<link rel=\"preload\" href=\"/dist/data\" as=\"fetch\" integrity=\"sha384-data\" crossorigin>"
+        );
+        assert_eq!(
+            render(
+                |path| PreloadableAsset::Font(path, Some("font/woff2".to_string())),
+                "https://fonts/a.woff2"
+            ),
+            "<link rel=\"preload\" href=\"https://fonts/a.woff2\" as=\"font\" type=\"font/woff2\" integrity=\"sha384-font\" crossorigin>"
+        );
+        assert_eq!(
+            render(
+                |path| PreloadableAsset::Image(path, Some("image/png".to_string())),
+                "dist/logo.png"
+            ),
+            "<link rel=\"preload\" href=\"/dist/logo.png\" as=\"image\" type=\"image/png\">"
+        );
+        assert_eq!(
+            render(PreloadableAsset::Prefetch, "dist/app.js"),
+            "<link rel=\"prefetch\" href=\"/dist/app.js\">"
+        );
+        assert_eq!(
+            render(PreloadableAsset::Stylesheet, "dist/other.css"),
+            "<link rel=\"preload\" href=\"/dist/other.css\" as=\"style\">"
+        );
+    }
+
+    #[test]
+    fn test_render_with_nonce() {
+        let context = RenderContext::new(&PathRenderer {}).with_nonce("abc");
+        let render = |asset: fn(String) -> PreloadableAsset, path: &str| {
+            asset(path.to_string()).render_with_context(&context)
+        };
+
+        assert_eq!(
+            render(PreloadableAsset::Module, "dist/app.js"),
+            "<link rel=\"modulepreload\" href=\"/dist/app.js\" nonce=\"abc\">"
+        );
+        assert_eq!(
+            render(PreloadableAsset::Stylesheet, "dist/app.css"),
+            "<link rel=\"preload\" href=\"/dist/app.css\" as=\"style\" nonce=\"abc\">"
+        );
+        assert_eq!(
+            render(
+                |path| PreloadableAsset::Font(path, Some("font/woff2".to_string())),
+                "dist/a.woff2"
+            ),
+            "<link rel=\"preload\" href=\"/dist/a.woff2\" as=\"font\" type=\"font/woff2\" crossorigin>"
+        );
+        assert_eq!(
+            render(PreloadableAsset::Prefetch, "dist/lazy.js"),
+            "<link rel=\"prefetch\" href=\"/dist/lazy.js\">"
+        );
+    }
 }
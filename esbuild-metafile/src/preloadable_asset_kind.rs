@@ -0,0 +1,17 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// How a resource is preloaded, see [`AssetTypeRegistry`](crate::asset_type_registry::AssetTypeRegistry).
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreloadableAssetKind {
+    Audio,
+    Fetch,
+    Font,
+    Image,
+    Json,
+    Module,
+    Stylesheet,
+    Video,
+    Wasm,
+}
@@ -1,13 +1,331 @@
 use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
 
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::diagnostic::Diagnostic;
+use crate::error::Error;
+use crate::input::Input;
+use crate::metafile_validator::validate;
 use crate::output::Output;
+use crate::vite_manifest::ViteManifest;
 
 /// The raw esbuild `--metafile` JSON. Build the query-optimized
 /// [`EsbuildMetafile`](crate::esbuild_metafile::EsbuildMetafile) from it with [`From`]/[`Into`].
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RawEsbuildMetafile {
+    #[serde(default)]
+    pub inputs: HashMap<String, Input>,
     pub outputs: HashMap<String, Output>,
 }
+
+fn deserialize<'de, TRead: serde_json::de::Read<'de>>(
+    mut deserializer: serde_json::Deserializer<TRead>,
+) -> Result<RawEsbuildMetafile, Error> {
+    let metafile = serde_path_to_error::deserialize(&mut deserializer)?;
+
+    deserializer.end()?;
+
+    Ok(metafile)
+}
+
+impl RawEsbuildMetafile {
+    pub fn from_path<TPath: AsRef<Path>>(path: TPath) -> Result<RawEsbuildMetafile, Error> {
+        let path = path.as_ref();
+        let contents = fs::read(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        deserialize(serde_json::Deserializer::from_slice(&contents))
+    }
+
+    /// Reads are not buffered, wrap unbuffered sources in a [`std::io::BufReader`].
+    pub fn from_reader<TRead: Read>(reader: TRead) -> Result<RawEsbuildMetafile, Error> {
+        deserialize(serde_json::Deserializer::from_reader(reader))
+    }
+
+    /// Checks the output graph for problems that would lead to missing assets: orphan
+    /// outputs, imports and css bundles that do not resolve, import cycles and entry points
+    /// claimed by more than one output. Diagnostics are sorted.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        validate(self)
+    }
+
+    pub fn to_vite_manifest(&self) -> ViteManifest {
+        ViteManifest::from_raw(self)
+    }
+}
+
+impl FromStr for RawEsbuildMetafile {
+    type Err = Error;
+
+    fn from_str(json: &str) -> Result<RawEsbuildMetafile, Error> {
+        deserialize(serde_json::Deserializer::from_str(json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use std::io::Write;
+
+    use super::*;
+    use crate::import_kind::ImportKind;
+    use crate::input_format::InputFormat;
+    use crate::test::ESBUILD_CONTENTS_FONTS;
+    use crate::test::get_raw_metafile_basic;
+    use crate::test::get_raw_metafile_fonts;
+
+    #[test]
+    fn test_inputs_are_deserialized() {
+        let metafile = get_raw_metafile_fonts();
+        let fonts = &metafile.inputs["resources/css/_fonts.css"];
+
+        assert_eq!(metafile.inputs.len(), 4);
+        assert_eq!(fonts.bytes, 1465);
+        assert_eq!(fonts.imports.len(), 2);
+        assert_eq!(fonts.imports[0].kind, Some(ImportKind::UrlToken));
+        assert!(fonts.imports[0].external);
+        assert!(fonts.format.is_none());
+
+        let page_common = &metafile.inputs["resources/css/page-common.css"];
+
+        assert_eq!(page_common.imports[0].kind, Some(ImportKind::ImportRule));
+        assert_eq!(
+            page_common.imports[0].original.as_deref(),
+            Some("./_reset.css")
+        );
+        assert!(!page_common.imports[0].external);
+    }
+
+    #[test]
+    fn test_outputs_are_deserialized() {
+        let metafile = get_raw_metafile_fonts();
+        let controller = &metafile.outputs["static/controller_foo_CTJMZK66.js"];
+
+        assert_eq!(controller.exports, vec!["controller_foo".to_string()]);
+        assert_eq!(
+            controller.imports[1].kind,
+            Some(ImportKind::ImportStatement)
+        );
+        assert_eq!(controller.imports[3].kind, Some(ImportKind::FileLoader));
+
+        let page_common = &metafile.outputs["static/page-common_DO3RNJ3I.css"];
+
+        assert_eq!(page_common.bytes, 1647);
+        assert_eq!(
+            page_common.inputs["resources/css/_fonts.css"].bytes_in_output,
+            717
+        );
+    }
+
+    #[test]
+    fn test_missing_inputs_section_defaults_to_empty() {
+        let metafile = get_raw_metafile_basic();
+
+        assert!(metafile.inputs.is_empty());
+        assert_eq!(metafile.outputs["dist/main.js"].bytes, 0);
+        assert!(metafile.outputs["dist/main.js"].imports[0].kind.is_none());
+    }
+
+    #[test]
+    fn test_import_attributes_and_format_are_deserialized() {
+        let metafile: RawEsbuildMetafile = serde_json::from_str(
+            r#"{
+                "inputs": {
+                    "src/data.json": {"bytes": 2, "imports": [], "with": {"type": "json"}},
+                    "src/main.js": {
+                        "bytes": 10,
+                        "format": "esm",
+                        "imports": [
+                            {
+                                "path": "src/data.json",
+                                "kind": "import-statement",
+                                "original": "./data.json",
+                                "with": {"type": "json"}
+                            },
+                            {"path": "src/lazy.js", "kind": "dynamic-import"},
+                            {"path": "src/legacy.js", "kind": "require-call"},
+                            {"path": "src/resolved.js", "kind": "require-resolve"},
+                            {"path": "src/entry.js", "kind": "entry-point"},
+                            {"path": "src/module.css", "kind": "composes-from"}
+                        ]
+                    },
+                    "src/legacy.js": {"bytes": 1, "imports": [], "format": "cjs"}
+                },
+                "outputs": {}
+            }"#,
+        )
+        .expect("metafile parses");
+        let main = &metafile.inputs["src/main.js"];
+
+        assert_eq!(main.format, Some(InputFormat::Esm));
+        assert_eq!(main.imports[0].with["type"], "json");
+        assert_eq!(
+            main.imports
+                .iter()
+                .map(|import| import.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                Some(ImportKind::ImportStatement),
+                Some(ImportKind::DynamicImport),
+                Some(ImportKind::RequireCall),
+                Some(ImportKind::RequireResolve),
+                Some(ImportKind::EntryPoint),
+                Some(ImportKind::ComposesFrom),
+            ]
+        );
+        assert_eq!(metafile.inputs["src/data.json"].with["type"], "json");
+        assert_eq!(
+            metafile.inputs["src/legacy.js"].format,
+            Some(InputFormat::Cjs)
+        );
+    }
+
+    #[test]
+    fn test_serialization_round_trips() {
+        let metafile = get_raw_metafile_fonts();
+        let json = serde_json::to_string(&metafile).expect("metafile serializes");
+        let reparsed: RawEsbuildMetafile = serde_json::from_str(&json).expect("metafile reparses");
+
+        assert_eq!(
+            serde_json::to_value(&metafile).expect("metafile serializes"),
+            serde_json::to_value(&reparsed).expect("metafile serializes")
+        );
+        assert!(!json.contains("\"original\":null"));
+        assert!(!json.contains("\"external\":false"));
+    }
+
+    #[test]
+    fn test_from_path_reads_file() {
+        let mut file = tempfile::NamedTempFile::new().expect("temp file is created");
+
+        file.write_all(ESBUILD_CONTENTS_FONTS.as_bytes())
+            .expect("temp file is written");
+
+        let metafile = RawEsbuildMetafile::from_path(file.path()).expect("metafile is loaded");
+
+        assert_eq!(metafile.outputs.len(), 5);
+    }
+
+    #[test]
+    fn test_from_path_reports_missing_file() {
+        let directory = tempfile::tempdir().expect("temp dir is created");
+        let path = directory.path().join("missing.json");
+        let error = RawEsbuildMetafile::from_path(&path).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "failed to read esbuild metafile from \"{}\"",
+                path.display()
+            )
+        );
+        assert!(matches!(
+            error,
+            Error::Io { path: error_path, source }
+                if error_path == path && source.kind() == ErrorKind::NotFound
+        ));
+    }
+
+    #[test]
+    fn test_from_reader_reads_metafile() {
+        let metafile = RawEsbuildMetafile::from_reader(ESBUILD_CONTENTS_FONTS.as_bytes())
+            .expect("metafile is loaded");
+
+        assert_eq!(metafile.inputs.len(), 4);
+    }
+
+    #[test]
+    fn test_from_reader_reports_read_failure() {
+        struct FailingReader;
+
+        impl Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk on fire"))
+            }
+        }
+
+        let error = RawEsbuildMetafile::from_reader(FailingReader).unwrap_err();
+
+        assert_eq!(error.to_string(), "failed to read esbuild metafile");
+        assert!(matches!(error, Error::Read(source) if source.to_string() == "disk on fire"));
+    }
+
+    #[test]
+    fn test_syntax_error_reports_line_and_column() {
+        let error = RawEsbuildMetafile::from_str("{\n  \"outputs\": {,\n}").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "esbuild metafile is not valid JSON at line 2, column 15"
+        );
+        assert!(matches!(
+            error,
+            Error::Syntax {
+                line: 2,
+                column: 15,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_trailing_characters_are_a_syntax_error() {
+        let error = RawEsbuildMetafile::from_str("{\"outputs\": {}} x").unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::Syntax {
+                line: 1,
+                column: 17,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_schema_error_names_output_key() {
+        let error = RawEsbuildMetafile::from_str(
+            r#"{"outputs": {"dist/main.js": {"imports": [{"path": 5}]}}}"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "esbuild metafile does not match the expected schema at \"outputs.dist/main.js.imports[0].path\""
+        );
+        assert!(matches!(
+            error,
+            Error::Schema { output_key: Some(output_key), .. } if output_key == "dist/main.js"
+        ));
+    }
+
+    #[test]
+    fn test_schema_error_outside_outputs_has_no_output_key() {
+        let error = RawEsbuildMetafile::from_str(r#"{"inputs": {"src/main.ts": {"bytes": "a"}}}"#)
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::Schema { output_key: None, path, .. } if path == "inputs.src/main.ts.bytes"
+        ));
+    }
+
+    #[test]
+    fn test_plain_serde_json_data_error_is_a_schema_error() {
+        let error: Error = serde_json::from_str::<RawEsbuildMetafile>("{}")
+            .unwrap_err()
+            .into();
+
+        assert!(matches!(
+            error,
+            Error::Schema { output_key: None, path, .. } if path.is_empty()
+        ));
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+/// An output that got a new file name even though the source inputs bundled into it did
+/// not change.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RehashedOutput {
+    pub before_path: String,
+    pub after_path: String,
+}
@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::sync::Weak;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::error::Error;
+use crate::esbuild_metafile::EsbuildMetafile;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Fingerprint {
+    len: u64,
+    modified: SystemTime,
+}
+
+fn read_fingerprint(path: &Path) -> Result<Fingerprint, Error> {
+    fs::metadata(path)
+        .and_then(|metadata| {
+            Ok(Fingerprint {
+                len: metadata.len(),
+                modified: metadata.modified()?,
+            })
+        })
+        .map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+fn load_metafile(path: &Path, aliases: &HashMap<String, String>) -> Result<EsbuildMetafile, Error> {
+    Ok(aliases.iter().fold(
+        EsbuildMetafile::from_path(path)?,
+        |metafile, (alias, input_path)| metafile.with_alias(alias, input_path),
+    ))
+}
+
+/// An [`EsbuildMetafile`] that is re-parsed whenever the file it was loaded from changes,
+/// meant for development servers running next to `esbuild --watch`.
+///
+/// Readers get an [`Arc`] snapshot from [`ReloadableMetafile::load`], so a request keeps
+/// seeing the same metafile even if a reload happens in the meantime. If the changed file
+/// cannot be read or parsed, the last good metafile keeps being served.
+#[derive(Debug)]
+pub struct ReloadableMetafile {
+    /// Applied to every metafile that is loaded, see [`EsbuildMetafile::with_alias`].
+    aliases: HashMap<String, String>,
+    current: RwLock<Arc<EsbuildMetafile>>,
+    fingerprint: Mutex<Fingerprint>,
+    /// The last reload failure [`ReloadableMetafile::poll`] logged, so failures that
+    /// repeat on every poll, like a missing file, are logged once.
+    last_failure: Mutex<Option<String>>,
+    path: PathBuf,
+}
+
+impl ReloadableMetafile {
+    pub fn from_path<TPath: AsRef<Path>>(path: TPath) -> Result<ReloadableMetafile, Error> {
+        Self::from_path_with_aliases(path, HashMap::new())
+    }
+
+    /// Like [`ReloadableMetafile::from_path`], with input aliases by alias that every
+    /// reloaded metafile gets too.
+    pub fn from_path_with_aliases<TPath: AsRef<Path>>(
+        path: TPath,
+        aliases: HashMap<String, String>,
+    ) -> Result<ReloadableMetafile, Error> {
+        let path = path.as_ref().to_path_buf();
+        let fingerprint = read_fingerprint(&path)?;
+        let metafile = load_metafile(&path, &aliases)?;
+
+        Ok(ReloadableMetafile {
+            aliases,
+            current: RwLock::new(Arc::new(metafile)),
+            fingerprint: Mutex::new(fingerprint),
+            last_failure: Mutex::new(None),
+            path,
+        })
+    }
+
+    pub fn load(&self) -> Arc<EsbuildMetafile> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Re-parses the metafile if its size or modification time changed since the last
+    /// attempt. Returns whether a new metafile was swapped in. A file that fails to parse
+    /// is not retried until it changes again.
+    pub fn reload_if_modified(&self) -> Result<bool, Error> {
+        let mut fingerprint = self
+            .fingerprint
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let current_fingerprint = read_fingerprint(&self.path)?;
+
+        if *fingerprint == current_fingerprint {
+            return Ok(false);
+        }
+
+        *fingerprint = current_fingerprint;
+
+        let metafile = load_metafile(&self.path, &self.aliases)?;
+
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(metafile);
+
+        Ok(true)
+    }
+
+    /// Checks for changes and returns the current snapshot. Reload failures are logged
+    /// once until they change, and the last good metafile is returned.
+    pub fn poll(&self) -> Arc<EsbuildMetafile> {
+        if let Some(error) = self.new_failure(self.reload_if_modified().err()) {
+            log::warn!(
+                "Keeping the previous esbuild metafile, reloading {:?} failed: {error}",
+                self.path
+            );
+        }
+
+        self.load()
+    }
+
+    /// Remembers the outcome of a reload, and returns the failure if it is not the one
+    /// that was reported last.
+    fn new_failure(&self, error: Option<Error>) -> Option<Error> {
+        let mut last_failure = self
+            .last_failure
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let failure = error.as_ref().map(|error| format!("{error:?}"));
+
+        if *last_failure == failure {
+            return None;
+        }
+
+        *last_failure = failure;
+
+        error
+    }
+
+    /// Polls for changes in a background thread every `interval`. The thread stops once
+    /// every other [`Arc`] to this metafile has been dropped.
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let metafile: Weak<ReloadableMetafile> = Arc::downgrade(self);
+
+        thread::spawn(move || {
+            loop {
+                thread::sleep(interval);
+
+                match metafile.upgrade() {
+                    Some(metafile) => {
+                        metafile.poll();
+                    }
+                    None => break,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::Instant;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::input_lookup::InputLookup;
+    use crate::test::ESBUILD_CONTENTS_BASIC;
+    use crate::test::ESBUILD_CONTENTS_GLB;
+
+    fn write_metafile(path: &Path, contents: &str, modified: SystemTime) {
+        fs::write(path, contents).expect("metafile is written");
+        File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(modified))
+            .expect("modification time is set");
+    }
+
+    fn metafile_in_temp_dir() -> (TempDir, PathBuf) {
+        let directory = tempfile::tempdir().expect("temp dir is created");
+        let path = directory.path().join("meta.json");
+
+        write_metafile(&path, ESBUILD_CONTENTS_BASIC, SystemTime::UNIX_EPOCH);
+
+        (directory, path)
+    }
+
+    fn has_input(metafile: &EsbuildMetafile, input_path: &str) -> bool {
+        matches!(metafile.input(input_path), InputLookup::Found(_))
+    }
+
+    #[test]
+    fn test_unchanged_file_is_not_reloaded() {
+        let (_directory, path) = metafile_in_temp_dir();
+        let metafile = ReloadableMetafile::from_path(&path).expect("metafile is loaded");
+        let snapshot = metafile.load();
+
+        assert_eq!(metafile.path(), path);
+        assert!(!metafile.reload_if_modified().expect("reload succeeds"));
+        assert!(Arc::ptr_eq(&snapshot, &metafile.load()));
+    }
+
+    #[test]
+    fn test_changed_file_is_swapped_in() {
+        let (_directory, path) = metafile_in_temp_dir();
+        let metafile = ReloadableMetafile::from_path(&path).expect("metafile is loaded");
+        let snapshot = metafile.load();
+
+        write_metafile(
+            &path,
+            ESBUILD_CONTENTS_GLB,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+        );
+
+        assert!(metafile.reload_if_modified().expect("reload succeeds"));
+        assert!(has_input(
+            &metafile.load(),
+            "resources/media/models/model.glb"
+        ));
+        assert!(!has_input(&snapshot, "resources/media/models/model.glb"));
+    }
+
+    #[test]
+    fn test_aliases_survive_reloads() {
+        let (_directory, path) = metafile_in_temp_dir();
+        let metafile = ReloadableMetafile::from_path_with_aliases(
+            &path,
+            HashMap::from([("main".to_string(), "src/main.ts".to_string())]),
+        )
+        .expect("metafile is loaded");
+
+        assert!(matches!(
+            metafile.load().resolve_input("main"),
+            Ok(InputLookup::Found(_))
+        ));
+
+        write_metafile(
+            &path,
+            ESBUILD_CONTENTS_BASIC,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+        );
+
+        assert!(metafile.reload_if_modified().expect("reload succeeds"));
+        assert!(matches!(
+            metafile.load().resolve_input("main"),
+            Ok(InputLookup::Found(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_file_keeps_last_good_metafile() {
+        let (_directory, path) = metafile_in_temp_dir();
+        let metafile = ReloadableMetafile::from_path(&path).expect("metafile is loaded");
+
+        write_metafile(
+            &path,
+            "{\"outputs\": ",
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+        );
+
+        assert!(matches!(
+            metafile.reload_if_modified(),
+            Err(Error::Syntax { .. })
+        ));
+        assert!(has_input(&metafile.poll(), "src/main.ts"));
+        assert!(
+            !metafile
+                .reload_if_modified()
+                .expect("broken file is not retried")
+        );
+    }
+
+    #[test]
+    fn test_removed_file_keeps_last_good_metafile() {
+        let (_directory, path) = metafile_in_temp_dir();
+        let metafile = ReloadableMetafile::from_path(&path).expect("metafile is loaded");
+
+        fs::remove_file(&path).expect("metafile is removed");
+
+        assert!(matches!(
+            metafile.reload_if_modified(),
+            Err(Error::Io { .. })
+        ));
+        assert!(has_input(&metafile.poll(), "src/main.ts"));
+    }
+
+    #[test]
+    fn test_repeated_failures_are_reported_once() {
+        let (_directory, path) = metafile_in_temp_dir();
+        let metafile = ReloadableMetafile::from_path(&path).expect("metafile is loaded");
+
+        fs::remove_file(&path).expect("metafile is removed");
+        metafile.poll();
+
+        assert!(
+            metafile
+                .new_failure(metafile.reload_if_modified().err())
+                .is_none()
+        );
+
+        write_metafile(
+            &path,
+            "{\"outputs\": ",
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+        );
+
+        assert!(matches!(
+            metafile.new_failure(metafile.reload_if_modified().err()),
+            Some(Error::Syntax { .. })
+        ));
+
+        write_metafile(
+            &path,
+            ESBUILD_CONTENTS_GLB,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(2),
+        );
+        metafile.poll();
+        fs::remove_file(&path).expect("metafile is removed again");
+
+        assert!(matches!(
+            metafile.new_failure(metafile.reload_if_modified().err()),
+            Some(Error::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_missing_file_fails_initial_load() {
+        let directory = tempfile::tempdir().expect("temp dir is created");
+
+        assert!(matches!(
+            ReloadableMetafile::from_path(directory.path().join("missing.json")),
+            Err(Error::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_file_fails_initial_load() {
+        let directory = tempfile::tempdir().expect("temp dir is created");
+        let path = directory.path().join("meta.json");
+
+        write_metafile(&path, "[]", SystemTime::UNIX_EPOCH);
+
+        assert!(matches!(
+            ReloadableMetafile::from_path(&path),
+            Err(Error::Schema { .. })
+        ));
+    }
+
+    #[test]
+    fn test_watcher_reloads_and_stops_with_metafile() {
+        let (_directory, path) = metafile_in_temp_dir();
+        let metafile = Arc::new(ReloadableMetafile::from_path(&path).expect("metafile is loaded"));
+        let watcher = metafile.watch(Duration::from_millis(1));
+
+        write_metafile(
+            &path,
+            ESBUILD_CONTENTS_GLB,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while !has_input(&metafile.load(), "resources/media/models/model.glb") {
+            assert!(
+                Instant::now() < deadline,
+                "watcher did not reload the metafile"
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        drop(metafile);
+        watcher.join().expect("watcher thread stops");
+    }
+}
@@ -0,0 +1,84 @@
+use crate::renders_path::RendersPath;
+use crate::script_options::ScriptOptions;
+use crate::script_rules::ScriptRules;
+use crate::subresource_integrity::SubresourceIntegrity;
+
+/// Everything needed to render asset tags for a single response.
+///
+/// Besides the [`RendersPath`] it can carry the per-request Content-Security-Policy
+/// nonce, which is added to every script, module preload and stylesheet tag, and the
+/// [`SubresourceIntegrity`] hashes of the outputs and the [`ScriptRules`] deciding how
+/// script tags are loaded.
+pub struct RenderContext<'render, TRendersPath: RendersPath> {
+    pub renders_path: &'render TRendersPath,
+    pub nonce: Option<&'render str>,
+    pub integrity: Option<&'render SubresourceIntegrity>,
+    pub script_rules: Option<&'render ScriptRules>,
+}
+
+impl<'render, TRendersPath: RendersPath> RenderContext<'render, TRendersPath> {
+    pub fn new(renders_path: &'render TRendersPath) -> Self {
+        Self {
+            renders_path,
+            nonce: None,
+            integrity: None,
+            script_rules: None,
+        }
+    }
+
+    pub fn with_nonce(mut self, nonce: &'render str) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn with_integrity(mut self, integrity: &'render SubresourceIntegrity) -> Self {
+        self.integrity = Some(integrity);
+        self
+    }
+
+    pub fn with_script_rules(mut self, script_rules: &'render ScriptRules) -> Self {
+        self.script_rules = Some(script_rules);
+        self
+    }
+
+    pub fn render_path(&self, path: &str) -> String {
+        self.renders_path.render_path(path)
+    }
+
+    pub fn integrity_of(&self, path: &str) -> Option<&'render str> {
+        self.integrity
+            .and_then(|integrity| integrity.integrity(path))
+    }
+
+    pub fn script_options(&self, path: &str) -> ScriptOptions {
+        self.script_rules
+            .map_or_else(ScriptOptions::default, |script_rules| {
+                script_rules.options(path)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_renderer::PathRenderer;
+
+    #[test]
+    fn test_integrity_of_without_hashes_is_none() {
+        let context = RenderContext::new(&PathRenderer {});
+
+        assert_eq!(context.integrity_of("dist/app.js"), None);
+    }
+
+    #[test]
+    fn test_integrity_of_looks_up_hashes() {
+        let integrity = SubresourceIntegrity::default().with_hash("dist/app.js", "sha384-js");
+        let context = RenderContext::new(&PathRenderer {})
+            .with_nonce("abc")
+            .with_integrity(&integrity);
+
+        assert_eq!(context.nonce, Some("abc"));
+        assert_eq!(context.integrity_of("dist/app.js"), Some("sha384-js"));
+        assert_eq!(context.render_path("dist/app.js"), "/dist/app.js");
+    }
+}
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScriptLoading {
+    /// Runs as soon as it is loaded, regardless of document order.
+    #[default]
+    Async,
+    /// Blocks the parser, for classic scripts that must run before the rest of the page.
+    Blocking,
+    /// Runs in document order once the document has been parsed.
+    Defer,
+}
+
+impl ScriptLoading {
+    pub fn attribute_name(self) -> Option<&'static str> {
+        match self {
+            ScriptLoading::Async => Some("async"),
+            ScriptLoading::Blocking => None,
+            ScriptLoading::Defer => Some("defer"),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::cross_origin::CrossOrigin;
+use crate::fetch_priority::FetchPriority;
+use crate::script_loading::ScriptLoading;
+use crate::script_type::ScriptType;
+
+/// How a script tag is rendered. The default is an `async` module script.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ScriptOptions {
+    pub cross_origin: Option<CrossOrigin>,
+    pub fetch_priority: Option<FetchPriority>,
+    pub loading: ScriptLoading,
+    pub script_type: ScriptType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_partial_options() {
+        let options: ScriptOptions =
+            serde_json::from_str(r#"{"loading":"defer","fetchPriority":"high"}"#).unwrap();
+
+        assert_eq!(
+            options,
+            ScriptOptions {
+                fetch_priority: Some(FetchPriority::High),
+                loading: ScriptLoading::Defer,
+                ..ScriptOptions::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use globset::Glob;
+use globset::GlobMatcher;
+
+use crate::asset_manifest::AssetManifest;
+use crate::error::Error;
+use crate::script_options::ScriptOptions;
+
+#[derive(Clone, Debug)]
+enum ScriptMatcher {
+    OutputPath(String),
+    Pattern(GlobMatcher),
+}
+
+impl ScriptMatcher {
+    fn is_match(&self, output_path: &str) -> bool {
+        match self {
+            ScriptMatcher::OutputPath(path) => path == output_path,
+            ScriptMatcher::Pattern(matcher) => matcher.is_match(output_path),
+        }
+    }
+}
+
+/// Chooses the [`ScriptOptions`] a script output is rendered with.
+///
+/// Rules are checked in the order they were added and the first match wins. Scripts
+/// no rule matches are rendered with the default options.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptRules {
+    default: ScriptOptions,
+    rules: Vec<(ScriptMatcher, ScriptOptions)>,
+}
+
+impl ScriptRules {
+    pub fn new(default: ScriptOptions) -> Self {
+        Self {
+            default,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Applies `options` to the entry output of an entry point, resolved with
+    /// [`AssetManifest::resolve_input_path`]. The chunks it shares with other entry points
+    /// keep their own options.
+    pub fn with_entry<TAssetManifest: AssetManifest + ?Sized>(
+        mut self,
+        manifest: &TAssetManifest,
+        input_path: &str,
+        options: ScriptOptions,
+    ) -> Result<Self, Error> {
+        let Some(entry_output) = manifest
+            .resolve_input_path(input_path)?
+            .and_then(|resolved_path| manifest.entry_output(&resolved_path))
+        else {
+            return Err(Error::InputNotFound {
+                input_path: input_path.to_string(),
+            });
+        };
+
+        self.rules
+            .push((ScriptMatcher::OutputPath(entry_output.to_string()), options));
+
+        Ok(self)
+    }
+
+    /// Applies `options` to script outputs whose path matches a glob pattern, like
+    /// `static/legacy_*.js`.
+    pub fn with_pattern(mut self, pattern: &str, options: ScriptOptions) -> Result<Self, Error> {
+        let matcher = Glob::new(pattern)
+            .map_err(|source| Error::Pattern {
+                pattern: pattern.to_string(),
+                source,
+            })?
+            .compile_matcher();
+
+        self.rules.push((ScriptMatcher::Pattern(matcher), options));
+
+        Ok(self)
+    }
+
+    pub fn options(&self, output_path: &str) -> ScriptOptions {
+        self.rules
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(output_path))
+            .map_or(self.default, |(_, options)| *options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::esbuild_metafile::EsbuildMetafile;
+    use crate::script_loading::ScriptLoading;
+    use crate::script_type::ScriptType;
+    use crate::test::get_metafile_dynamic;
+    use crate::test::get_metafile_fonts;
+
+    const DEFER: ScriptOptions = ScriptOptions {
+        cross_origin: None,
+        fetch_priority: None,
+        loading: ScriptLoading::Defer,
+        script_type: ScriptType::Module,
+    };
+
+    const CLASSIC: ScriptOptions = ScriptOptions {
+        cross_origin: None,
+        fetch_priority: None,
+        loading: ScriptLoading::Blocking,
+        script_type: ScriptType::Classic,
+    };
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = ScriptRules::default()
+            .with_entry(
+                &get_metafile_fonts(),
+                "resources/ts/controller_foo.tsx",
+                DEFER,
+            )
+            .unwrap()
+            .with_pattern("static/*.js", CLASSIC)
+            .unwrap();
+
+        assert_eq!(rules.options("static/controller_foo_CTJMZK66.js"), DEFER);
+        assert_eq!(rules.options("static/controller_bar_ABCDEFGH.js"), CLASSIC);
+        assert_eq!(rules.options("other/app.js"), ScriptOptions::default());
+    }
+
+    #[test]
+    fn test_shared_chunks_keep_their_options() {
+        let metafile = get_metafile_dynamic();
+        let rules = ScriptRules::default()
+            .with_entry(&metafile, "src/main.ts", DEFER)
+            .unwrap()
+            .with_entry(&metafile, "src/lazy.ts", CLASSIC)
+            .unwrap();
+
+        assert_eq!(rules.options("dist/main.js"), DEFER);
+        assert_eq!(rules.options("dist/lazy.js"), CLASSIC);
+        assert_eq!(
+            rules.options("dist/chunk-shared.js"),
+            ScriptOptions::default()
+        );
+        assert_eq!(
+            rules.options("dist/chunk-lazy.js"),
+            ScriptOptions::default()
+        );
+    }
+
+    #[test]
+    fn test_entries_are_resolved() {
+        let rules = ScriptRules::default()
+            .with_entry(
+                &get_metafile_fonts(),
+                "./resources\\ts\\controller_foo.tsx",
+                DEFER,
+            )
+            .unwrap();
+
+        assert_eq!(rules.options("static/controller_foo_CTJMZK66.js"), DEFER);
+
+        let metafile: EsbuildMetafile = r#"{"outputs": {
+            "dist/a.js": {"imports": [], "entryPoint": "src/a.ts"},
+            "dist/b.js": {"imports": [], "entryPoint": "file:src/a.ts"}
+        }}"#
+        .parse()
+        .unwrap();
+
+        assert!(matches!(
+            ScriptRules::default().with_entry(&metafile, "./src/a.ts", DEFER),
+            Err(Error::AmbiguousInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_default_options_can_be_replaced() {
+        assert_eq!(ScriptRules::new(CLASSIC).options("static/app.js"), CLASSIC);
+    }
+
+    #[test]
+    fn test_unknown_entry_is_an_error() {
+        let error = ScriptRules::default()
+            .with_entry(&get_metafile_fonts(), "resources/ts/missing.tsx", DEFER)
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::InputNotFound { input_path } if input_path == "resources/ts/missing.tsx"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_an_error() {
+        let error = ScriptRules::default()
+            .with_pattern("static/[.js", DEFER)
+            .unwrap_err();
+
+        assert!(matches!(error, Error::Pattern { pattern, .. } if pattern == "static/[.js"));
+    }
+}
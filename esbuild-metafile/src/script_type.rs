@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScriptType {
+    /// IIFE or other non-module bundles, rendered without a `type` attribute.
+    Classic,
+    #[default]
+    Module,
+}
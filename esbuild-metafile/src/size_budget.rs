@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::size_budget_kind::SizeBudgetKind;
+
+/// A byte limit for everything a glob pattern matches, like "entry
+/// `resources/ts/controller_foo.tsx` closure <= 150 KiB" or "any `**/chunk-*.js` <= 80 KiB".
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeBudget {
+    pub kind: SizeBudgetKind,
+    pub pattern: String,
+    pub max_bytes: u64,
+}
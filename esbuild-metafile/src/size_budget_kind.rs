@@ -0,0 +1,21 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SizeBudgetKind {
+    /// Limits an entry point together with the stylesheets and chunks it pulls in. The
+    /// pattern matches entry point input paths.
+    EntryClosure,
+    /// Limits every single output. The pattern matches output paths.
+    Output,
+}
+
+impl SizeBudgetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SizeBudgetKind::EntryClosure => "entry-closure",
+            SizeBudgetKind::Output => "output",
+        }
+    }
+}
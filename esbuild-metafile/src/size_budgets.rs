@@ -0,0 +1,294 @@
+use globset::Glob;
+use globset::GlobMatcher;
+
+use crate::budget_evaluation::BudgetEvaluation;
+use crate::budget_result::BudgetResult;
+use crate::error::Error;
+use crate::esbuild_metafile::EsbuildMetafile;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+use crate::size_budget::SizeBudget;
+use crate::size_budget_kind::SizeBudgetKind;
+use crate::size_report::SizeReport;
+
+/// A set of [`SizeBudget`]s to check metafiles against, e.g. to gate merges on bundle size.
+///
+/// Budgets whose pattern matches nothing are reported as unmatched and fail the
+/// evaluation.
+#[derive(Clone, Debug, Default)]
+pub struct SizeBudgets {
+    budgets: Vec<(GlobMatcher, SizeBudget)>,
+}
+
+impl SizeBudgets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the budgets from a list, e.g. one deserialized from a JSON config file.
+    pub fn from_budgets<TBudgets: IntoIterator<Item = SizeBudget>>(
+        budgets: TBudgets,
+    ) -> Result<Self, Error> {
+        budgets
+            .into_iter()
+            .try_fold(Self::new(), |budgets, budget| budgets.with_budget(budget))
+    }
+
+    pub fn with_budget(mut self, budget: SizeBudget) -> Result<Self, Error> {
+        let matcher = Glob::new(&budget.pattern)
+            .map_err(|source| Error::Pattern {
+                pattern: budget.pattern.clone(),
+                source,
+            })?
+            .compile_matcher();
+
+        self.budgets.push((matcher, budget));
+
+        Ok(self)
+    }
+
+    /// Limits the closure of every entry point matching `pattern` to `max_bytes`.
+    pub fn with_entry_closure(self, pattern: &str, max_bytes: u64) -> Result<Self, Error> {
+        self.with_budget(SizeBudget {
+            kind: SizeBudgetKind::EntryClosure,
+            pattern: pattern.to_string(),
+            max_bytes,
+        })
+    }
+
+    /// Limits every output matching `pattern` to `max_bytes`.
+    pub fn with_output(self, pattern: &str, max_bytes: u64) -> Result<Self, Error> {
+        self.with_budget(SizeBudget {
+            kind: SizeBudgetKind::Output,
+            pattern: pattern.to_string(),
+            max_bytes,
+        })
+    }
+
+    /// Sizes come from the raw metafile, entry closures from the lookup index built from
+    /// it. Results follow the order the budgets were added in, then the matched paths.
+    pub fn evaluate(
+        &self,
+        raw_metafile: &RawEsbuildMetafile,
+        metafile: &EsbuildMetafile,
+    ) -> BudgetEvaluation {
+        let report = SizeReport::new(raw_metafile, metafile, 0);
+        let mut output_paths: Vec<&String> = raw_metafile.outputs.keys().collect();
+        let mut results = Vec::new();
+        let mut unmatched = Vec::new();
+
+        output_paths.sort();
+
+        for (matcher, budget) in &self.budgets {
+            let result_count = results.len();
+
+            match budget.kind {
+                SizeBudgetKind::EntryClosure => results.extend(
+                    report
+                        .entries
+                        .iter()
+                        .filter(|entry| matcher.is_match(&entry.entry_point))
+                        .map(|entry| {
+                            BudgetResult::new(budget, &entry.entry_point, entry.closure_bytes)
+                        }),
+                ),
+                SizeBudgetKind::Output => results.extend(
+                    output_paths
+                        .iter()
+                        .filter(|output_path| matcher.is_match(output_path))
+                        .map(|output_path| {
+                            BudgetResult::new(
+                                budget,
+                                output_path,
+                                raw_metafile.outputs[*output_path].bytes,
+                            )
+                        }),
+                ),
+            }
+
+            if results.len() == result_count {
+                unmatched.push(budget.clone());
+            }
+        }
+
+        BudgetEvaluation {
+            results,
+            unmatched,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::get_raw_metafile_sizes;
+
+    fn evaluate(budgets: &SizeBudgets) -> BudgetEvaluation {
+        let raw_metafile = get_raw_metafile_sizes();
+
+        budgets.evaluate(&raw_metafile, &EsbuildMetafile::from(&raw_metafile))
+    }
+
+    #[test]
+    fn test_entry_closure_budget() {
+        let evaluation = evaluate(
+            &SizeBudgets::new()
+                .with_entry_closure("src/app.ts", 50_000)
+                .unwrap()
+                .with_entry_closure("src/admin.*", 50_000)
+                .unwrap(),
+        );
+
+        assert!(!evaluation.passed());
+        assert_eq!(
+            evaluation.results,
+            vec![
+                BudgetResult {
+                    budget: SizeBudget {
+                        kind: SizeBudgetKind::EntryClosure,
+                        pattern: "src/app.ts".to_string(),
+                        max_bytes: 50_000,
+                    },
+                    path: "src/app.ts".to_string(),
+                    actual_bytes: 55_000,
+                    passed: false,
+                    overshoot_bytes: 5_000,
+                },
+                BudgetResult {
+                    budget: SizeBudget {
+                        kind: SizeBudgetKind::EntryClosure,
+                        pattern: "src/admin.*".to_string(),
+                        max_bytes: 50_000,
+                    },
+                    path: "src/admin.ts".to_string(),
+                    actual_bytes: 48_000,
+                    passed: true,
+                    overshoot_bytes: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            evaluation
+                .failures()
+                .map(|result| result.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["src/app.ts"]
+        );
+    }
+
+    #[test]
+    fn test_output_budget() {
+        let evaluation = evaluate(&SizeBudgets::new().with_output("**/*.js", 80_000).unwrap());
+
+        assert_eq!(
+            evaluation
+                .results
+                .iter()
+                .map(|result| (result.path.as_str(), result.passed, result.overshoot_bytes))
+                .collect::<Vec<_>>(),
+            vec![
+                ("dist/admin-EEEE.js", true, 0),
+                ("dist/app-AAAA.js", true, 0),
+                ("dist/chunk-SHARED.js", true, 0),
+                ("dist/lazy-CCCC.js", false, 10_000),
+            ]
+        );
+        assert!(evaluate(&SizeBudgets::new().with_output("**/*.css", 80_000).unwrap()).passed());
+        assert!(evaluate(&SizeBudgets::new()).passed());
+    }
+
+    #[test]
+    fn test_unmatched_budgets_fail() {
+        let evaluation = evaluate(
+            &SizeBudgets::new()
+                .with_entry_closure("src/ap.ts", 50_000)
+                .unwrap()
+                .with_output("dist/*.mjs", 80_000)
+                .unwrap()
+                .with_output("dist/chunk-*.js", 80_000)
+                .unwrap(),
+        );
+
+        assert!(!evaluation.passed());
+        assert_eq!(evaluation.failures().count(), 0);
+        assert_eq!(
+            evaluation
+                .unmatched
+                .iter()
+                .map(|budget| budget.pattern.as_str())
+                .collect::<Vec<_>>(),
+            vec!["src/ap.ts", "dist/*.mjs"]
+        );
+        assert_eq!(
+            evaluation.render_text(),
+            [
+                "pass output dist/chunk-SHARED.js: 39.1 KiB <= 78.1 KiB\n",
+                "FAIL entry-closure src/ap.ts: matches nothing\n",
+                "FAIL output dist/*.mjs: matches nothing\n",
+            ]
+            .concat()
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&evaluation.to_json()).unwrap();
+
+        assert_eq!(
+            json["unmatched"][1],
+            serde_json::json!({"kind": "output", "pattern": "dist/*.mjs", "maxBytes": 80_000})
+        );
+    }
+
+    #[test]
+    fn test_from_budgets() {
+        let budgets: Vec<SizeBudget> = serde_json::from_str(
+            r#"[
+                {"kind": "entry-closure", "pattern": "src/*.ts", "maxBytes": 153600},
+                {"kind": "output", "pattern": "dist/chunk-*.js", "maxBytes": 81920}
+            ]"#,
+        )
+        .unwrap();
+        let evaluation = evaluate(&SizeBudgets::from_budgets(budgets).unwrap());
+
+        assert!(evaluation.passed());
+        assert_eq!(evaluation.results.len(), 4);
+        assert!(matches!(
+            SizeBudgets::from_budgets([SizeBudget {
+                kind: SizeBudgetKind::Output,
+                pattern: "dist/[".to_string(),
+                max_bytes: 0,
+            }]),
+            Err(Error::Pattern { pattern, .. }) if pattern == "dist/["
+        ));
+    }
+
+    #[test]
+    fn test_render() {
+        let evaluation = evaluate(
+            &SizeBudgets::new()
+                .with_entry_closure("src/app.ts", 50_000)
+                .unwrap()
+                .with_output("dist/chunk-*.js", 80_000)
+                .unwrap(),
+        );
+
+        assert_eq!(
+            evaluation.render_text(),
+            [
+                "FAIL entry-closure src/app.ts: 53.7 KiB > 48.8 KiB (+4.9 KiB)\n",
+                "pass output dist/chunk-SHARED.js: 39.1 KiB <= 78.1 KiB\n",
+            ]
+            .concat()
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&evaluation.to_json()).unwrap();
+
+        assert_eq!(
+            json["results"][0],
+            serde_json::json!({
+                "budget": {"kind": "entry-closure", "pattern": "src/app.ts", "maxBytes": 50_000},
+                "path": "src/app.ts",
+                "actualBytes": 55_000,
+                "passed": false,
+                "overshootBytes": 5_000
+            })
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::asset::Asset;
+use crate::byte_size::format_bytes;
+use crate::entry_size::EntrySize;
+use crate::esbuild_metafile::EsbuildMetafile;
+use crate::input_contribution::InputContribution;
+use crate::input_lookup::InputLookup;
+use crate::output_lookup::OutputLookup;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+
+/// How many contributing inputs each entry keeps unless told otherwise.
+pub const DEFAULT_TOP_INPUTS: usize = 10;
+
+fn measure_entry(
+    raw_metafile: &RawEsbuildMetafile,
+    metafile: &EsbuildMetafile,
+    entry_point: &str,
+    output_path: &str,
+    top_inputs: usize,
+) -> EntrySize {
+    let mut closure_paths: BTreeSet<&str> = BTreeSet::new();
+    let input_lookup = metafile.input(entry_point);
+    let output_lookup = metafile.output(output_path);
+
+    if let InputLookup::Found(input) = &input_lookup {
+        closure_paths.extend(input.outputs.iter().map(AsRef::as_ref));
+    }
+
+    if let OutputLookup::Found(output) = &output_lookup {
+        closure_paths.extend(output.preloads.iter().map(AsRef::as_ref));
+    }
+
+    closure_paths.remove(output_path);
+
+    let mut own_bytes = 0;
+    let mut stylesheet_bytes = 0;
+    let mut chunk_bytes = 0;
+    let mut contributions: HashMap<&str, u64> = HashMap::new();
+    let mut count = |output_path: &str, bytes: &mut u64| {
+        if let Some(output) = raw_metafile.outputs.get(output_path) {
+            *bytes += output.bytes;
+
+            for (input_path, input) in &output.inputs {
+                *contributions.entry(input_path).or_default() += input.bytes_in_output;
+            }
+        }
+    };
+
+    count(output_path, &mut own_bytes);
+
+    for path in closure_paths {
+        match Asset::from_path(path.to_string()) {
+            Asset::Script(_) => count(path, &mut chunk_bytes),
+            Asset::Stylesheet(_) => count(path, &mut stylesheet_bytes),
+            Asset::Unknown(_) => {}
+        }
+    }
+
+    let mut contributions: Vec<InputContribution> = contributions
+        .into_iter()
+        .map(|(input_path, bytes_in_output)| InputContribution {
+            input_path: input_path.to_string(),
+            bytes_in_output,
+        })
+        .collect();
+
+    contributions.sort_by(|left, right| {
+        right
+            .bytes_in_output
+            .cmp(&left.bytes_in_output)
+            .then_with(|| left.input_path.cmp(&right.input_path))
+    });
+    contributions.truncate(top_inputs);
+
+    EntrySize {
+        entry_point: entry_point.to_string(),
+        output_path: output_path.to_string(),
+        own_bytes,
+        stylesheet_bytes,
+        chunk_bytes,
+        closure_bytes: own_bytes + stylesheet_bytes + chunk_bytes,
+        top_inputs: contributions,
+    }
+}
+
+/// Bundle size analysis of every entry point in a metafile, sorted by entry point.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SizeReport {
+    pub entries: Vec<EntrySize>,
+}
+
+impl SizeReport {
+    /// Sizes come from the raw metafile, the closure of each entry from the lookup index
+    /// built from it.
+    pub fn new(
+        raw_metafile: &RawEsbuildMetafile,
+        metafile: &EsbuildMetafile,
+        top_inputs: usize,
+    ) -> Self {
+        Self {
+            entries: metafile
+                .entry_points()
+                .into_iter()
+                .map(|(entry_point, output_path)| {
+                    measure_entry(raw_metafile, metafile, entry_point, output_path, top_inputs)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn entry(&self, entry_point: &str) -> Option<&EntrySize> {
+        self.entries
+            .iter()
+            .find(|entry| entry.entry_point == entry_point)
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut text = String::new();
+
+        for entry in &self.entries {
+            let _ = writeln!(text, "{} -> {}", entry.entry_point, entry.output_path);
+            let _ = writeln!(text, "  own: {}", format_bytes(entry.own_bytes));
+            let _ = writeln!(
+                text,
+                "  stylesheets: {}",
+                format_bytes(entry.stylesheet_bytes)
+            );
+            let _ = writeln!(text, "  chunks: {}", format_bytes(entry.chunk_bytes));
+            let _ = writeln!(text, "  closure: {}", format_bytes(entry.closure_bytes));
+
+            if !entry.top_inputs.is_empty() {
+                let _ = writeln!(text, "  top inputs:");
+            }
+
+            for input in &entry.top_inputs {
+                let _ = writeln!(
+                    text,
+                    "    {} {}",
+                    format_bytes(input.bytes_in_output),
+                    input.input_path
+                );
+            }
+        }
+
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("size report always serializes")
+    }
+}
+
+impl From<&RawEsbuildMetafile> for SizeReport {
+    fn from(raw_metafile: &RawEsbuildMetafile) -> Self {
+        Self::new(
+            raw_metafile,
+            &EsbuildMetafile::from(raw_metafile),
+            DEFAULT_TOP_INPUTS,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::get_raw_metafile_fonts;
+    use crate::test::get_raw_metafile_sizes;
+
+    fn contribution(input_path: &str, bytes_in_output: u64) -> InputContribution {
+        InputContribution {
+            input_path: input_path.to_string(),
+            bytes_in_output,
+        }
+    }
+
+    #[test]
+    fn test_entry_closure() {
+        let report = SizeReport::from(&get_raw_metafile_sizes());
+
+        assert_eq!(
+            report.entry("src/app.ts"),
+            Some(&EntrySize {
+                entry_point: "src/app.ts".to_string(),
+                output_path: "dist/app-AAAA.js".to_string(),
+                own_bytes: 12_000,
+                stylesheet_bytes: 3_000,
+                chunk_bytes: 40_000,
+                closure_bytes: 55_000,
+                top_inputs: vec![
+                    contribution("node_modules/lodash-es/lodash.js", 35_000),
+                    contribution("node_modules/preact/dist/preact.module.js", 10_000),
+                    contribution("src/shared.ts", 4_000),
+                    contribution("src/app.css", 2_900),
+                    contribution("src/app.ts", 1_500),
+                ],
+            })
+        );
+        assert_eq!(
+            report
+                .entries
+                .iter()
+                .map(|entry| (entry.entry_point.as_str(), entry.closure_bytes))
+                .collect::<Vec<_>>(),
+            vec![("src/admin.ts", 48_000), ("src/app.ts", 55_000), ("src/lazy.ts", 130_000)]
+        );
+        assert_eq!(report.entry("src/missing.ts"), None);
+    }
+
+    #[test]
+    fn test_top_inputs_limit_and_ties() {
+        let raw_metafile = get_raw_metafile_fonts();
+        let report = SizeReport::new(&raw_metafile, &EsbuildMetafile::from(&raw_metafile), 2);
+        let page_common = report
+            .entry("resources/css/page-common.css")
+            .expect("css entry is reported");
+
+        assert_eq!(page_common.own_bytes, 1_647);
+        assert_eq!(page_common.closure_bytes, 1_647);
+        assert_eq!(
+            page_common.top_inputs,
+            vec![
+                contribution("resources/css/_fonts.css", 717),
+                contribution("resources/css/_reset.css", 704),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_text() {
+        let raw_metafile = get_raw_metafile_sizes();
+        let report = SizeReport::new(&raw_metafile, &EsbuildMetafile::from(&raw_metafile), 1);
+
+        assert_eq!(
+            report.render_text(),
+            [
+                "src/admin.ts -> dist/admin-EEEE.js\n",
+                "  own: 7.8 KiB\n",
+                "  stylesheets: 0 B\n",
+                "  chunks: 39.1 KiB\n",
+                "  closure: 46.9 KiB\n",
+                "  top inputs:\n",
+                "    34.2 KiB node_modules/lodash-es/lodash.js\n",
+                "src/app.ts -> dist/app-AAAA.js\n",
+                "  own: 11.7 KiB\n",
+                "  stylesheets: 2.9 KiB\n",
+                "  chunks: 39.1 KiB\n",
+                "  closure: 53.7 KiB\n",
+                "  top inputs:\n",
+                "    34.2 KiB node_modules/lodash-es/lodash.js\n",
+                "src/lazy.ts -> dist/lazy-CCCC.js\n",
+                "  own: 87.9 KiB\n",
+                "  stylesheets: 0 B\n",
+                "  chunks: 39.1 KiB\n",
+                "  closure: 127.0 KiB\n",
+                "  top inputs:\n",
+                "    86.9 KiB src/lazy.ts\n",
+            ]
+            .concat()
+        );
+        assert_eq!(
+            SizeReport::new(&raw_metafile, &EsbuildMetafile::from(&raw_metafile), 0)
+                .render_text()
+                .lines()
+                .count(),
+            15
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let report = SizeReport::from(&get_raw_metafile_sizes());
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+        assert_eq!(json["entries"][1]["closureBytes"], 55_000);
+        assert_eq!(
+            json["entries"][1]["topInputs"][0],
+            serde_json::json!({
+                "inputPath": "node_modules/lodash-es/lodash.js",
+                "bytesInOutput": 35_000
+            })
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::asset_manifest::AssetManifest;
+use crate::input_lookup::InputLookup;
+use crate::input_properties::InputProperties;
+use crate::output_lookup::OutputLookup;
+use crate::output_properties::OutputProperties;
+
+fn to_shared_paths(paths: &[&str]) -> Arc<[Arc<str>]> {
+    paths.iter().map(|path| Arc::from(*path)).collect()
+}
+
+/// An [`AssetManifest`] listed by hand, for projects without a bundler manifest or
+/// for tests.
+#[derive(Clone, Debug, Default)]
+pub struct StaticAssetManifest {
+    entry_outputs: HashMap<String, Arc<str>>,
+    inputs: HashMap<String, InputProperties>,
+    outputs: HashMap<String, OutputProperties>,
+}
+
+impl StaticAssetManifest {
+    /// Lists an entry point whose script is `entry_output`, which is what
+    /// [`ScriptRules::with_entry`](crate::script_rules::ScriptRules::with_entry) applies
+    /// to. It is added to the end of `outputs` if it is not listed there.
+    pub fn with_entry(
+        self,
+        input_path: &str,
+        entry_output: &str,
+        outputs: &[&str],
+        static_paths: &[&str],
+    ) -> Self {
+        let mut outputs = outputs.to_vec();
+
+        if !outputs.contains(&entry_output) {
+            outputs.push(entry_output);
+        }
+
+        let mut manifest = self.with_input(input_path, &outputs, static_paths);
+
+        manifest
+            .entry_outputs
+            .insert(input_path.to_string(), Arc::from(entry_output));
+        manifest
+    }
+
+    /// Lists an input without an entry output, replacing one listed with
+    /// [`StaticAssetManifest::with_entry`].
+    pub fn with_input(mut self, input_path: &str, outputs: &[&str], static_paths: &[&str]) -> Self {
+        self.entry_outputs.remove(input_path);
+        self.inputs.insert(
+            input_path.to_string(),
+            InputProperties {
+                outputs: to_shared_paths(outputs),
+                static_paths: to_shared_paths(static_paths),
+            },
+        );
+        self
+    }
+
+    pub fn with_output(
+        mut self,
+        output_path: &str,
+        preloads: &[&str],
+        prefetches: &[&str],
+    ) -> Self {
+        self.outputs.insert(
+            output_path.to_string(),
+            OutputProperties {
+                prefetches: to_shared_paths(prefetches),
+                preloads: to_shared_paths(preloads),
+            },
+        );
+        self
+    }
+}
+
+impl AssetManifest for StaticAssetManifest {
+    fn input(&self, input_path: &str) -> InputLookup {
+        match self.inputs.get(input_path) {
+            Some(input) => InputLookup::Found(input.clone()),
+            None => InputLookup::NotFound,
+        }
+    }
+
+    fn output(&self, output_path: &str) -> OutputLookup {
+        match self.outputs.get(output_path) {
+            Some(output) => OutputLookup::Found(output.clone()),
+            None => OutputLookup::NotFound,
+        }
+    }
+
+    fn entry_output(&self, input_path: &str) -> Option<Arc<str>> {
+        self.entry_outputs.get(input_path).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::Asset;
+    use crate::asset_collector::AssetCollector;
+    use crate::path_renderer::PathRenderer;
+
+    #[test]
+    fn test_renders_listed_assets() {
+        let manifest = StaticAssetManifest::default()
+            .with_entry("app", "dist/app.js", &["dist/app.css"], &[])
+            .with_input("logo", &[], &["dist/logo.png"])
+            .with_output("dist/app.js", &["dist/vendor.js"], &["dist/lazy.js"]);
+        let collector = AssetCollector::new(Arc::new(manifest.clone()));
+
+        assert!(collector.add("app").unwrap());
+        assert!(!collector.add("missing").unwrap());
+        assert_eq!(
+            collector.render(&PathRenderer {}),
+            [
+                "<link rel=\"stylesheet\" href=\"/dist/app.css\">\n",
+                "<script async src=\"/dist/app.js\" type=\"module\"></script>\n",
+                "<link rel=\"modulepreload\" href=\"/dist/vendor.js\">\n",
+                "<link rel=\"prefetch\" href=\"/dist/lazy.js\">",
+            ]
+            .concat()
+        );
+        assert!(matches!(
+            manifest.input("logo"),
+            InputLookup::Found(InputProperties { static_paths, .. }) if static_paths.len() == 1
+        ));
+        assert_eq!(manifest.output("dist/vendor.js"), OutputLookup::NotFound);
+        assert_eq!(manifest.entry_output("app"), Some(Arc::from("dist/app.js")));
+        assert_eq!(manifest.entry_output("logo"), None);
+    }
+
+    #[test]
+    fn test_entry_output_does_not_depend_on_order() {
+        let manifest = StaticAssetManifest::default()
+            .with_entry(
+                "app",
+                "dist/app.js",
+                &["dist/app.js", "dist/vendor.js"],
+                &[],
+            )
+            .with_entry("admin", "dist/admin.js", &[], &[])
+            .with_entry("replaced", "dist/replaced.js", &[], &[])
+            .with_input("replaced", &["dist/replaced.js"], &[]);
+        let collector = AssetCollector::new(Arc::new(manifest.clone()));
+
+        assert!(collector.add("app").unwrap());
+        assert_eq!(
+            collector
+                .collect()
+                .assets
+                .iter()
+                .map(Asset::path)
+                .collect::<Vec<_>>(),
+            vec!["dist/app.js", "dist/vendor.js"]
+        );
+        assert_eq!(manifest.entry_output("app"), Some(Arc::from("dist/app.js")));
+        assert_eq!(
+            manifest.entry_output("admin"),
+            Some(Arc::from("dist/admin.js"))
+        );
+        assert_eq!(manifest.entry_output("replaced"), None);
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use sha2::Digest;
+use sha2::Sha384;
+
+use crate::error::Error;
+use crate::esbuild_metafile::EsbuildMetafile;
+use crate::filesystem::is_absolute_url;
+
+pub fn compute_integrity(contents: &[u8]) -> String {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(contents)))
+}
+
+/// `sha384` Subresource Integrity metadata for the outputs of a build, keyed by output path.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SubresourceIntegrity {
+    hashes: HashMap<String, String>,
+}
+
+impl SubresourceIntegrity {
+    /// Hashes every output of the metafile except source maps, reading it relative to
+    /// `output_root`. Absolute URLs are not part of the build, their integrity can be
+    /// supplied with [`SubresourceIntegrity::with_hash`].
+    pub fn from_output_root<TPath: AsRef<Path>>(
+        metafile: &EsbuildMetafile,
+        output_root: TPath,
+    ) -> Result<SubresourceIntegrity, Error> {
+        let mut hashes: HashMap<String, String> = HashMap::new();
+
+        for output_path in metafile.get_output_paths() {
+            if is_absolute_url(&output_path) || output_path.ends_with(".map") {
+                continue;
+            }
+
+            let path = output_root.as_ref().join(&output_path);
+            let contents = fs::read(&path).map_err(|source| Error::OutputRead {
+                path,
+                source,
+            })?;
+
+            hashes.insert(output_path, compute_integrity(&contents));
+        }
+
+        Ok(SubresourceIntegrity {
+            hashes,
+        })
+    }
+
+    pub fn with_hash(mut self, path: &str, integrity: &str) -> Self {
+        self.hashes.insert(path.to_string(), integrity.to_string());
+        self
+    }
+
+    pub fn with_hashes(mut self, hashes: HashMap<String, String>) -> Self {
+        self.hashes.extend(hashes);
+        self
+    }
+
+    pub fn integrity(&self, path: &str) -> Option<&str> {
+        self.hashes.get(path).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::test::ESBUILD_CONTENTS_BASIC;
+
+    fn write_output_root() -> tempfile::TempDir {
+        let directory = tempfile::tempdir().expect("temp dir is created");
+
+        fs::create_dir(directory.path().join("dist")).expect("dist dir is created");
+        fs::write(directory.path().join("dist/main.js"), "console.log(1);\n")
+            .expect("js is written");
+        fs::write(directory.path().join("dist/main.css"), "").expect("css is written");
+
+        directory
+    }
+
+    #[test]
+    fn test_compute_integrity_matches_known_digest() {
+        assert_eq!(
+            compute_integrity(b""),
+            "sha384-OLBgp1GsljhM2TJ+sbHjaiH9txEUvgdDTAzHv2P24donTt6/529l+9Ua0vFImLlb"
+        );
+    }
+
+    #[test]
+    fn test_outputs_are_hashed_from_output_root() {
+        let output_root = write_output_root();
+        let metafile =
+            EsbuildMetafile::from_str(ESBUILD_CONTENTS_BASIC).expect("basic fixture parses");
+        let integrity = SubresourceIntegrity::from_output_root(&metafile, output_root.path())
+            .expect("outputs are hashed");
+
+        assert_eq!(
+            integrity.integrity("dist/main.css"),
+            Some("sha384-OLBgp1GsljhM2TJ+sbHjaiH9txEUvgdDTAzHv2P24donTt6/529l+9Ua0vFImLlb")
+        );
+        assert_eq!(
+            integrity.integrity("dist/main.js"),
+            Some(compute_integrity(b"console.log(1);\n").as_str())
+        );
+        assert_eq!(integrity.integrity("dist/chunk1.js"), None);
+    }
+
+    #[test]
+    fn test_absolute_urls_are_skipped_and_can_be_supplied() {
+        let output_root = write_output_root();
+        let metafile = EsbuildMetafile::from_str(
+            r#"{"outputs": {
+                "dist/main.js": {"imports": [], "entryPoint": "src/main.ts"},
+                "https://fonts/font.woff2": {"imports": []},
+                "//cdn/lib.js": {"imports": []}
+            }}"#,
+        )
+        .expect("metafile parses");
+        let integrity = SubresourceIntegrity::from_output_root(&metafile, output_root.path())
+            .expect("outputs are hashed")
+            .with_hash("https://fonts/font.woff2", "sha384-font")
+            .with_hashes(HashMap::from([(
+                "//cdn/lib.js".to_string(),
+                "sha384-lib".to_string(),
+            )]));
+
+        assert_eq!(
+            integrity.integrity("https://fonts/font.woff2"),
+            Some("sha384-font")
+        );
+        assert_eq!(integrity.integrity("//cdn/lib.js"), Some("sha384-lib"));
+    }
+
+    #[test]
+    fn test_source_maps_are_not_hashed() {
+        let output_root = write_output_root();
+        let metafile = EsbuildMetafile::from_str(
+            r#"{"outputs": {
+                "dist/main.js": {"imports": [], "entryPoint": "src/main.ts"},
+                "dist/main.js.map": {"imports": []}
+            }}"#,
+        )
+        .expect("metafile parses");
+        let integrity = SubresourceIntegrity::from_output_root(&metafile, output_root.path())
+            .expect("source maps are not read");
+
+        assert!(integrity.integrity("dist/main.js").is_some());
+        assert_eq!(integrity.integrity("dist/main.js.map"), None);
+    }
+
+    #[test]
+    fn test_missing_output_reports_path() {
+        let directory = tempfile::tempdir().expect("temp dir is created");
+        let metafile =
+            EsbuildMetafile::from_str(ESBUILD_CONTENTS_BASIC).expect("basic fixture parses");
+        let error =
+            SubresourceIntegrity::from_output_root(&metafile, directory.path()).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("failed to read build output from \"")
+        );
+        assert!(matches!(
+            error,
+            Error::OutputRead { path, .. } if path.starts_with(directory.path()) && path.to_string_lossy().contains("dist/main.")
+        ));
+    }
+}
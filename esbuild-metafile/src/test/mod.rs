@@ -2,13 +2,20 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::esbuild_metafile::EsbuildMetafile;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
 
-const ESBUILD_CONTENTS_BASIC: &str = include_str!("./fixtures/esbuild-meta-basic.json");
-const ESBUILD_CONTENTS_DEDUP: &str = include_str!("./fixtures/esbuild-meta-dedup.json");
-const ESBUILD_CONTENTS_FONTS: &str = include_str!("./fixtures/esbuild-meta-fonts.json");
-const ESBUILD_CONTENTS_GLB: &str = include_str!("./fixtures/esbuild-meta-glb.json");
-const ESBUILD_CONTENTS_ORPHAN: &str = include_str!("./fixtures/esbuild-meta-orphan.json");
-const ESBUILD_CONTENTS_SVG: &str = include_str!("./fixtures/esbuild-meta-svg.json");
+pub const ESBUILD_CONTENTS_BASIC: &str = include_str!("./fixtures/esbuild-meta-basic.json");
+pub const ESBUILD_CONTENTS_DEDUP: &str = include_str!("./fixtures/esbuild-meta-dedup.json");
+pub const ESBUILD_CONTENTS_DYNAMIC: &str = include_str!("./fixtures/esbuild-meta-dynamic.json");
+pub const ESBUILD_CONTENTS_FONTS: &str = include_str!("./fixtures/esbuild-meta-fonts.json");
+pub const ESBUILD_CONTENTS_GLB: &str = include_str!("./fixtures/esbuild-meta-glb.json");
+pub const ESBUILD_CONTENTS_INVALID: &str = include_str!("./fixtures/esbuild-meta-invalid.json");
+pub const ESBUILD_CONTENTS_ORPHAN: &str = include_str!("./fixtures/esbuild-meta-orphan.json");
+pub const ESBUILD_CONTENTS_SIZES: &str = include_str!("./fixtures/esbuild-meta-sizes.json");
+pub const ESBUILD_CONTENTS_SIZES_NEXT: &str =
+    include_str!("./fixtures/esbuild-meta-sizes-next.json");
+pub const ESBUILD_CONTENTS_SVG: &str = include_str!("./fixtures/esbuild-meta-svg.json");
+pub const VITE_MANIFEST: &str = include_str!("./fixtures/vite-manifest.json");
 
 pub fn get_metafile_basic() -> Arc<EsbuildMetafile> {
     Arc::new(EsbuildMetafile::from_str(ESBUILD_CONTENTS_BASIC).expect("basic fixture parses"))
@@ -18,6 +25,10 @@ pub fn get_metafile_dedup() -> Arc<EsbuildMetafile> {
     Arc::new(EsbuildMetafile::from_str(ESBUILD_CONTENTS_DEDUP).expect("dedup fixture parses"))
 }
 
+pub fn get_metafile_dynamic() -> Arc<EsbuildMetafile> {
+    Arc::new(EsbuildMetafile::from_str(ESBUILD_CONTENTS_DYNAMIC).expect("dynamic fixture parses"))
+}
+
 pub fn get_metafile_fonts() -> Arc<EsbuildMetafile> {
     Arc::new(EsbuildMetafile::from_str(ESBUILD_CONTENTS_FONTS).expect("fonts fixture parses"))
 }
@@ -33,3 +44,35 @@ pub fn get_metafile_orphan() -> Arc<EsbuildMetafile> {
 pub fn get_metafile_svg() -> Arc<EsbuildMetafile> {
     Arc::new(EsbuildMetafile::from_str(ESBUILD_CONTENTS_SVG).expect("svg fixture parses"))
 }
+
+pub fn get_raw_metafile_basic() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_BASIC).expect("basic fixture parses")
+}
+
+pub fn get_raw_metafile_fonts() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_FONTS).expect("fonts fixture parses")
+}
+
+pub fn get_raw_metafile_dynamic() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_DYNAMIC).expect("dynamic fixture parses")
+}
+
+pub fn get_raw_metafile_glb() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_GLB).expect("glb fixture parses")
+}
+
+pub fn get_raw_metafile_invalid() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_INVALID).expect("invalid fixture parses")
+}
+
+pub fn get_raw_metafile_orphan() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_ORPHAN).expect("orphan fixture parses")
+}
+
+pub fn get_raw_metafile_sizes() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_SIZES).expect("sizes fixture parses")
+}
+
+pub fn get_raw_metafile_sizes_next() -> RawEsbuildMetafile {
+    serde_json::from_str(ESBUILD_CONTENTS_SIZES_NEXT).expect("sizes next fixture parses")
+}
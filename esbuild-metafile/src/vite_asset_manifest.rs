@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::asset_manifest::AssetManifest;
+use crate::error::Error;
+use crate::input_lookup::InputLookup;
+use crate::input_properties::InputProperties;
+use crate::output_lookup::OutputLookup;
+use crate::output_properties::OutputProperties;
+use crate::path_interner::PathInterner;
+use crate::vite_manifest::ViteManifest;
+use crate::vite_manifest_chunk::ViteManifestChunk;
+
+fn push_unique<'manifest>(
+    paths: &mut Vec<&'manifest str>,
+    seen: &mut HashSet<&'manifest str>,
+    path: &'manifest str,
+) {
+    if seen.insert(path) {
+        paths.push(path);
+    }
+}
+
+/// Keys of every chunk reachable from `roots`, in breadth-first order, following static
+/// imports and, if `follow_dynamic` is set, dynamic imports too.
+fn reachable_keys<'manifest>(
+    manifest: &'manifest ViteManifest,
+    roots: &'manifest [String],
+    follow_dynamic: bool,
+) -> Vec<&'manifest str> {
+    let mut keys: Vec<&str> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut position = 0;
+
+    for root in roots {
+        push_unique(&mut keys, &mut seen, root);
+    }
+
+    while let Some(key) = keys.get(position).copied() {
+        position += 1;
+
+        if let Some(chunk) = manifest.chunk(key) {
+            let dynamic_imports: &[String] = if follow_dynamic {
+                &chunk.dynamic_imports
+            } else {
+                &[]
+            };
+
+            for import in chunk.imports.iter().chain(dynamic_imports) {
+                push_unique(&mut keys, &mut seen, import);
+            }
+        }
+    }
+
+    keys
+}
+
+struct ChunkClosure<'manifest> {
+    outputs: Vec<&'manifest str>,
+    prefetches: Vec<&'manifest str>,
+    preloads: Vec<&'manifest str>,
+}
+
+/// Collects what a chunk needs the way Vite's backend integration guide describes:
+/// stylesheets of the chunk and of everything it imports statically, the imported chunks
+/// and their assets as preloads, and dynamically imported chunks as prefetches.
+fn collect_chunk_closure<'manifest>(
+    manifest: &'manifest ViteManifest,
+    chunk: &'manifest ViteManifestChunk,
+) -> ChunkClosure<'manifest> {
+    let mut outputs = Vec::new();
+    let mut preloads = Vec::new();
+    let mut prefetches = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let imported: Vec<&ViteManifestChunk> = reachable_keys(manifest, &chunk.imports, false)
+        .into_iter()
+        .filter_map(|key| manifest.chunk(key))
+        .collect();
+
+    seen.insert(&chunk.file);
+
+    for css in imported.iter().chain([&chunk]).flat_map(|chunk| &chunk.css) {
+        push_unique(&mut outputs, &mut seen, css);
+    }
+
+    outputs.push(&chunk.file);
+
+    for imported_chunk in &imported {
+        push_unique(&mut preloads, &mut seen, &imported_chunk.file);
+    }
+
+    for asset in [&chunk]
+        .into_iter()
+        .chain(&imported)
+        .flat_map(|chunk| &chunk.assets)
+    {
+        push_unique(&mut preloads, &mut seen, asset);
+    }
+
+    let dynamic_roots: Vec<String> = [&chunk]
+        .into_iter()
+        .chain(&imported)
+        .flat_map(|chunk| chunk.dynamic_imports.iter().cloned())
+        .collect();
+
+    for key in reachable_keys(manifest, &dynamic_roots, true) {
+        if let Some(dynamic_chunk) = manifest.chunk(key) {
+            push_unique(&mut prefetches, &mut seen, &dynamic_chunk.file);
+        }
+    }
+
+    ChunkClosure {
+        outputs,
+        prefetches,
+        preloads,
+    }
+}
+
+/// An [`AssetManifest`] over a Vite `manifest.json`, so projects moving between esbuild
+/// and Vite can keep the same templates.
+///
+/// Entry and dynamic entry chunks are looked up by their manifest key. Other chunks with
+/// a `src`, usually copied assets, resolve to their file as a static path.
+///
+/// Unlike [`EsbuildMetafile`](crate::esbuild_metafile::EsbuildMetafile), which lists the
+/// chunks an entry imports among its outputs and renders them as scripts, chunks are only
+/// preloaded here, as Vite's backend integration guide describes.
+#[derive(Debug, Default)]
+pub struct ViteAssetManifest {
+    entry_outputs: HashMap<Arc<str>, Arc<str>>,
+    inputs: HashMap<Arc<str>, InputProperties>,
+    outputs: HashMap<Arc<str>, OutputProperties>,
+}
+
+impl ViteAssetManifest {
+    pub fn from_path<TPath: AsRef<Path>>(path: TPath) -> Result<ViteAssetManifest, Error> {
+        Ok(ViteManifest::from_path(path)?.into())
+    }
+
+    pub fn from_reader<TRead: Read>(reader: TRead) -> Result<ViteAssetManifest, Error> {
+        Ok(ViteManifest::from_reader(reader)?.into())
+    }
+}
+
+impl From<ViteManifest> for ViteAssetManifest {
+    fn from(manifest: ViteManifest) -> Self {
+        let mut interner = PathInterner::default();
+        let mut entry_outputs = HashMap::new();
+        let mut inputs = HashMap::new();
+        let mut outputs = HashMap::new();
+        let no_paths: Arc<[Arc<str>]> = Arc::from([]);
+
+        for (key, chunk) in &manifest.chunks {
+            let closure = collect_chunk_closure(&manifest, chunk);
+
+            if chunk.is_entry || chunk.is_dynamic_entry {
+                entry_outputs.insert(interner.intern(key), interner.intern(&chunk.file));
+                inputs.insert(
+                    interner.intern(key),
+                    InputProperties {
+                        outputs: interner.intern_all(&closure.outputs),
+                        static_paths: no_paths.clone(),
+                    },
+                );
+            } else if let Some(src) = &chunk.src {
+                inputs.insert(
+                    interner.intern(src),
+                    InputProperties {
+                        outputs: no_paths.clone(),
+                        static_paths: interner.intern_all(&[&chunk.file]),
+                    },
+                );
+            }
+
+            outputs.insert(
+                interner.intern(&chunk.file),
+                OutputProperties {
+                    prefetches: interner.intern_all(&closure.prefetches),
+                    preloads: interner.intern_all(&closure.preloads),
+                },
+            );
+        }
+
+        Self {
+            entry_outputs,
+            inputs,
+            outputs,
+        }
+    }
+}
+
+impl FromStr for ViteAssetManifest {
+    type Err = Error;
+
+    fn from_str(json: &str) -> Result<ViteAssetManifest, Error> {
+        Ok(ViteManifest::from_str(json)?.into())
+    }
+}
+
+impl AssetManifest for ViteAssetManifest {
+    fn input(&self, input_path: &str) -> InputLookup {
+        match self.inputs.get(input_path) {
+            Some(input) => InputLookup::Found(input.clone()),
+            None => InputLookup::NotFound,
+        }
+    }
+
+    fn output(&self, output_path: &str) -> OutputLookup {
+        match self.outputs.get(output_path) {
+            Some(output) => OutputLookup::Found(output.clone()),
+            None => OutputLookup::NotFound,
+        }
+    }
+
+    fn entry_output(&self, input_path: &str) -> Option<Arc<str>> {
+        self.entry_outputs.get(input_path).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset_collector::AssetCollector;
+    use crate::esbuild_metafile::EsbuildMetafile;
+    use crate::path_renderer::PathRenderer;
+    use crate::test::VITE_MANIFEST;
+    use crate::test::get_raw_metafile_dynamic;
+
+    fn paths(paths: &[Arc<str>]) -> Vec<&str> {
+        paths.iter().map(|path| path.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_entry_lookup() {
+        let manifest = ViteAssetManifest::from_str(VITE_MANIFEST).unwrap();
+        let InputLookup::Found(input) = manifest.input("views/foo.js") else {
+            panic!("entry not found");
+        };
+        let OutputLookup::Found(output) = manifest.output("assets/foo-BRBmoGS9.js") else {
+            panic!("entry output not found");
+        };
+
+        assert_eq!(
+            paths(&input.outputs),
+            vec!["assets/shared-ChJ_j-JJ.css", "assets/foo-5UjPuW-k.css", "assets/foo-BRBmoGS9.js",]
+        );
+        assert!(input.static_paths.is_empty());
+        assert_eq!(
+            paths(&output.preloads),
+            vec!["assets/shared-B7PI925R.js", "assets/logo-tIm1L0Gs.png"]
+        );
+        assert_eq!(
+            paths(&output.prefetches),
+            vec!["assets/baz-B2H3sXNv.js", "assets/lazy-dep-C1aX0.js"]
+        );
+        assert_eq!(
+            manifest.entry_output("views/foo.js"),
+            Some(Arc::from("assets/foo-BRBmoGS9.js"))
+        );
+        assert_eq!(manifest.entry_output("images/logo.png"), None);
+    }
+
+    #[test]
+    fn test_assets_are_static_paths() {
+        let manifest = ViteAssetManifest::from_str(VITE_MANIFEST).unwrap();
+        let InputLookup::Found(input) = manifest.input("images/logo.png") else {
+            panic!("asset not found");
+        };
+
+        assert!(input.outputs.is_empty());
+        assert_eq!(paths(&input.static_paths), vec!["assets/logo-tIm1L0Gs.png"]);
+        assert_eq!(manifest.input("_shared-B7PI925R.js"), InputLookup::NotFound);
+        assert_eq!(manifest.output("assets/missing.js"), OutputLookup::NotFound);
+    }
+
+    #[test]
+    fn test_collector_renders_vite_entries() {
+        let collector = AssetCollector::new(Arc::new(
+            ViteAssetManifest::from_str(VITE_MANIFEST).unwrap(),
+        ));
+
+        collector.add("views/foo.js").unwrap();
+        collector.add("views/bar.js").unwrap();
+
+        assert_eq!(
+            collector.render(&PathRenderer {}),
+            [
+                "<link rel=\"stylesheet\" href=\"/assets/shared-ChJ_j-JJ.css\">\n",
+                "<link rel=\"stylesheet\" href=\"/assets/foo-5UjPuW-k.css\">\n",
+                "<script async src=\"/assets/foo-BRBmoGS9.js\" type=\"module\"></script>\n",
+                "<script async src=\"/assets/bar-gkvgaI9m.js\" type=\"module\"></script>\n",
+                "<link rel=\"modulepreload\" href=\"/assets/shared-B7PI925R.js\">\n",
+                "<link rel=\"preload\" href=\"/assets/logo-tIm1L0Gs.png\" as=\"image\" type=\"image/png\">\n",
+                "<link rel=\"prefetch\" href=\"/assets/baz-B2H3sXNv.js\">\n",
+                "<link rel=\"prefetch\" href=\"/assets/lazy-dep-C1aX0.js\">",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_exported_manifest_preloads_imported_chunks() {
+        let raw = get_raw_metafile_dynamic();
+        let esbuild = EsbuildMetafile::from(&raw);
+        let vite = ViteAssetManifest::from(raw.to_vite_manifest());
+        let sorted = |paths: &[Arc<str>]| {
+            let mut paths: Vec<String> = paths.iter().map(|path| path.to_string()).collect();
+
+            paths.sort();
+            paths
+        };
+        let (InputLookup::Found(esbuild_input), InputLookup::Found(vite_input)) =
+            (esbuild.input("src/main.ts"), vite.input("src/main.ts"))
+        else {
+            panic!("entry not found");
+        };
+        let (OutputLookup::Found(esbuild_output), OutputLookup::Found(vite_output)) =
+            (esbuild.output("dist/main.js"), vite.output("dist/main.js"))
+        else {
+            panic!("entry output not found");
+        };
+
+        assert_eq!(
+            vite.entry_output("src/main.ts"),
+            esbuild.entry_output("src/main.ts")
+        );
+        assert_eq!(
+            paths(&esbuild_input.outputs),
+            vec!["dist/chunk-shared.js", "dist/main.js"]
+        );
+        assert_eq!(paths(&vite_input.outputs), vec!["dist/main.js"]);
+        assert_eq!(
+            paths(&vite_output.preloads),
+            paths(&esbuild_output.preloads)
+        );
+        assert_eq!(
+            sorted(&vite_output.prefetches),
+            sorted(&esbuild_output.prefetches)
+        );
+    }
+
+    #[test]
+    fn test_from_path_and_reader() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+
+        std::io::Write::write_all(&mut file, VITE_MANIFEST.as_bytes()).unwrap();
+
+        assert!(matches!(
+            ViteAssetManifest::from_path(file.path())
+                .unwrap()
+                .input("views/foo.js"),
+            InputLookup::Found(_)
+        ));
+        assert!(matches!(
+            ViteAssetManifest::from_reader(VITE_MANIFEST.as_bytes())
+                .unwrap()
+                .input("views/bar.js"),
+            InputLookup::Found(_)
+        ));
+        let error =
+            ViteAssetManifest::from_path(file.path().with_extension("missing")).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("failed to read Vite manifest from \"")
+        );
+        assert!(matches!(error, Error::ViteManifestRead { .. }));
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::asset_type_registry::AssetTypeRegistry;
+use crate::error::Error;
+use crate::filesystem::get_file_extension;
+use crate::import::Import;
+use crate::import_kind::ImportKind;
+use crate::preloadable_asset_kind::PreloadableAssetKind;
+use crate::raw_esbuild_metafile::RawEsbuildMetafile;
+use crate::vite_manifest_chunk::ViteManifestChunk;
+
+fn is_module(path: &str) -> bool {
+    AssetTypeRegistry::default_registry().kind(path) == PreloadableAssetKind::Module
+}
+
+fn push_unique(paths: &mut Vec<String>, path: &str) {
+    if !paths.iter().any(|existing| existing == path) {
+        paths.push(path.to_string());
+    }
+}
+
+/// Picks the manifest key of every output the way Vite does: entry points are keyed by
+/// their input path, emitted assets by their source file if it is free, and everything
+/// else by its file name prefixed with `_`.
+fn assign_keys<'metafile>(
+    metafile: &'metafile RawEsbuildMetafile,
+    output_paths: &[&'metafile String],
+) -> HashMap<&'metafile str, String> {
+    let mut keys: HashMap<&str, String> = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut claim = |output_path: &'metafile str, candidates: Vec<String>| {
+        let key = candidates
+            .into_iter()
+            .find(|candidate| !taken.contains(candidate))
+            .unwrap_or_else(|| format!("_{output_path}"));
+
+        taken.insert(key.clone());
+        keys.insert(output_path, key);
+    };
+
+    for output_path in output_paths {
+        if let Some(entry_point) = &metafile.outputs[*output_path].entry_point {
+            claim(output_path, vec![entry_point.clone()]);
+        }
+    }
+
+    for output_path in output_paths {
+        let output = &metafile.outputs[*output_path];
+
+        if output.entry_point.is_none() {
+            let file_name = output_path.rsplit('/').next().unwrap_or(output_path);
+            let mut candidates: Vec<String> = Vec::new();
+
+            if !is_module(output_path) && output.inputs.len() == 1 {
+                candidates.extend(output.inputs.keys().cloned());
+            }
+
+            candidates.push(format!("_{file_name}"));
+            claim(output_path, candidates);
+        }
+    }
+
+    keys
+}
+
+/// A Vite-compatible `manifest.json`, keyed by entry point input path.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct ViteManifest {
+    pub chunks: BTreeMap<String, ViteManifestChunk>,
+}
+
+impl ViteManifest {
+    /// Source maps are left out. CSS bundles are listed in the `css` of their entry and
+    /// only get their own key if something else imports them. Files keep their esbuild
+    /// output paths, including the outdir, unlike the `outDir`-relative files of Vite.
+    pub fn from_raw(metafile: &RawEsbuildMetafile) -> Self {
+        let mut output_paths: Vec<&String> = metafile
+            .outputs
+            .keys()
+            .filter(|output_path| get_file_extension(output_path) != Some("map"))
+            .collect();
+
+        output_paths.sort();
+
+        let imported: HashSet<&str> = metafile
+            .outputs
+            .values()
+            .flat_map(|output| output.imports.iter())
+            .map(|import| import.path.as_str())
+            .collect();
+        let css_bundles: HashSet<&str> = metafile
+            .outputs
+            .values()
+            .filter_map(|output| output.css_bundle.as_deref())
+            .collect();
+
+        output_paths.retain(|output_path| {
+            metafile.outputs[*output_path].entry_point.is_some()
+                || !css_bundles.contains(output_path.as_str())
+                || imported.contains(output_path.as_str())
+        });
+
+        let keys = assign_keys(metafile, &output_paths);
+        let mut dynamically_imported: HashSet<&str> = HashSet::new();
+        let mut chunks = BTreeMap::new();
+
+        for output_path in &output_paths {
+            let output = &metafile.outputs[*output_path];
+            let mut chunk = ViteManifestChunk {
+                file: output_path.to_string(),
+                is_entry: output.entry_point.is_some(),
+                ..ViteManifestChunk::default()
+            };
+
+            if let Some(entry_point) = &output.entry_point {
+                chunk.src = Some(entry_point.clone());
+            } else if !is_module(output_path) && output.inputs.len() == 1 {
+                chunk.src = output.inputs.keys().next().cloned();
+            }
+
+            if let Some(css_bundle) = &output.css_bundle {
+                push_unique(&mut chunk.css, css_bundle);
+            }
+
+            for Import {
+                path,
+                kind,
+                external,
+                ..
+            } in &output.imports
+            {
+                if *external {
+                    continue;
+                }
+
+                let key = keys.get(path.as_str());
+
+                if kind.is_some_and(ImportKind::is_dynamic) {
+                    if let Some(key) = key {
+                        dynamically_imported.insert(path);
+                        push_unique(&mut chunk.dynamic_imports, key);
+                    }
+                } else {
+                    match (AssetTypeRegistry::default_registry().kind(path), key) {
+                        (PreloadableAssetKind::Module, Some(key)) => {
+                            push_unique(&mut chunk.imports, key)
+                        }
+                        (PreloadableAssetKind::Module, None) => {}
+                        (PreloadableAssetKind::Stylesheet, _) => push_unique(&mut chunk.css, path),
+                        _ => push_unique(&mut chunk.assets, path),
+                    }
+                }
+            }
+
+            chunks.insert(keys[output_path.as_str()].clone(), chunk);
+        }
+
+        for output_path in dynamically_imported {
+            if let Some(chunk) = chunks.get_mut(&keys[output_path]) {
+                chunk.is_dynamic_entry = true;
+            }
+        }
+
+        Self {
+            chunks,
+        }
+    }
+
+    pub fn from_path<TPath: AsRef<Path>>(path: TPath) -> Result<ViteManifest, Error> {
+        let path = path.as_ref();
+        let contents = fs::read(path).map_err(|source| Error::ViteManifestRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        serde_json::from_slice(&contents).map_err(Error::vite_manifest_parse)
+    }
+
+    /// Reads are not buffered, wrap unbuffered sources in a [`std::io::BufReader`].
+    pub fn from_reader<TRead: Read>(reader: TRead) -> Result<ViteManifest, Error> {
+        serde_json::from_reader(reader).map_err(Error::vite_manifest_parse)
+    }
+
+    pub fn chunk(&self, key: &str) -> Option<&ViteManifestChunk> {
+        self.chunks.get(key)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("manifest always serializes")
+    }
+}
+
+impl From<&RawEsbuildMetafile> for ViteManifest {
+    fn from(metafile: &RawEsbuildMetafile) -> Self {
+        Self::from_raw(metafile)
+    }
+}
+
+impl FromStr for ViteManifest {
+    type Err = Error;
+
+    fn from_str(json: &str) -> Result<ViteManifest, Error> {
+        serde_json::from_str(json).map_err(Error::vite_manifest_parse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::esbuild_metafile::EsbuildMetafile;
+    use crate::input_lookup::InputLookup;
+    use crate::output_lookup::OutputLookup;
+    use crate::test::ESBUILD_CONTENTS_BASIC;
+    use crate::test::ESBUILD_CONTENTS_DEDUP;
+    use crate::test::ESBUILD_CONTENTS_DYNAMIC;
+    use crate::test::ESBUILD_CONTENTS_FONTS;
+    use crate::test::ESBUILD_CONTENTS_GLB;
+    use crate::test::ESBUILD_CONTENTS_INVALID;
+    use crate::test::ESBUILD_CONTENTS_ORPHAN;
+    use crate::test::ESBUILD_CONTENTS_SIZES;
+    use crate::test::ESBUILD_CONTENTS_SIZES_NEXT;
+    use crate::test::ESBUILD_CONTENTS_SVG;
+    use crate::test::get_raw_metafile_dynamic;
+    use crate::test::get_raw_metafile_fonts;
+
+    #[test]
+    fn test_fixtures_round_trip() {
+        for contents in [
+            ESBUILD_CONTENTS_BASIC,
+            ESBUILD_CONTENTS_DEDUP,
+            ESBUILD_CONTENTS_DYNAMIC,
+            ESBUILD_CONTENTS_FONTS,
+            ESBUILD_CONTENTS_GLB,
+            ESBUILD_CONTENTS_INVALID,
+            ESBUILD_CONTENTS_ORPHAN,
+            ESBUILD_CONTENTS_SIZES,
+            ESBUILD_CONTENTS_SIZES_NEXT,
+            ESBUILD_CONTENTS_SVG,
+        ] {
+            let raw = RawEsbuildMetafile::from_str(contents).unwrap();
+            let manifest = raw.to_vite_manifest();
+            let metafile = EsbuildMetafile::from(raw);
+
+            assert_eq!(
+                ViteManifest::from_str(&manifest.to_json()).unwrap(),
+                manifest
+            );
+
+            for (key, chunk) in &manifest.chunks {
+                for import in chunk.imports.iter().chain(&chunk.dynamic_imports) {
+                    assert!(manifest.chunk(import).is_some(), "{key} imports {import}");
+                }
+
+                if let Some(src) = chunk.src.as_deref().filter(|_| chunk.is_entry) {
+                    let InputLookup::Found(input) = metafile.input(src) else {
+                        panic!("{src} is not an input");
+                    };
+
+                    assert!(input.outputs.iter().any(|path| **path == chunk.file));
+                    assert!(
+                        chunk
+                            .css
+                            .iter()
+                            .filter(|css| metafile.output(css) != OutputLookup::NotFound)
+                            .all(|css| input.outputs.iter().any(|path| **path == *css))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fonts_manifest() {
+        let manifest = get_raw_metafile_fonts().to_vite_manifest();
+
+        assert_eq!(
+            manifest.chunks.keys().collect::<Vec<_>>(),
+            vec![
+                "resources/css/page-common.css",
+                "resources/css/test.svg",
+                "resources/ts/controller_foo.tsx",
+            ]
+        );
+        assert_eq!(
+            manifest.chunk("resources/ts/controller_foo.tsx"),
+            Some(&ViteManifestChunk {
+                file: "static/controller_foo_CTJMZK66.js".to_string(),
+                src: Some("resources/ts/controller_foo.tsx".to_string()),
+                is_entry: true,
+                css: vec!["static/controller_foo_CX2Z63ZH.css".to_string()],
+                assets: vec!["static/logo_XSTJPNLH.png".to_string()],
+                ..ViteManifestChunk::default()
+            })
+        );
+        assert_eq!(
+            manifest.chunk("resources/css/page-common.css"),
+            Some(&ViteManifestChunk {
+                file: "static/page-common_DO3RNJ3I.css".to_string(),
+                src: Some("resources/css/page-common.css".to_string()),
+                is_entry: true,
+                assets: vec!["static/test_6D5OPEBZ.svg".to_string()],
+                ..ViteManifestChunk::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_dynamic_manifest() {
+        let manifest = ViteManifest::from(&get_raw_metafile_dynamic());
+
+        assert_eq!(
+            manifest.to_json(),
+            r#"{
+  "_chunk-lazy.js": {
+    "file": "dist/chunk-lazy.js",
+    "dynamicImports": [
+      "src/lazy.ts"
+    ]
+  },
+  "_chunk-shared.js": {
+    "file": "dist/chunk-shared.js"
+  },
+  "src/lazy.ts": {
+    "file": "dist/lazy.js",
+    "src": "src/lazy.ts",
+    "isEntry": true,
+    "isDynamicEntry": true,
+    "imports": [
+      "_chunk-shared.js",
+      "_chunk-lazy.js"
+    ]
+  },
+  "src/main.ts": {
+    "file": "dist/main.js",
+    "src": "src/main.ts",
+    "isEntry": true,
+    "imports": [
+      "_chunk-shared.js"
+    ],
+    "dynamicImports": [
+      "src/lazy.ts"
+    ]
+  }
+}"#
+        );
+    }
+
+    #[test]
+    fn test_asset_key_collision_falls_back_to_file_name() {
+        let manifest = RawEsbuildMetafile::from_str(ESBUILD_CONTENTS_SVG)
+            .unwrap()
+            .to_vite_manifest();
+
+        assert_eq!(
+            manifest
+                .chunk("_image_123.svg")
+                .map(|chunk| chunk.src.as_deref()),
+            Some(Some("resources/images/image.svg"))
+        );
+        assert_eq!(
+            manifest
+                .chunk("resources/images/image.svg")
+                .map(|chunk| chunk.file.as_str()),
+            Some("dist/image_123.js")
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keys_fall_back_to_output_path() {
+        let manifest = RawEsbuildMetafile::from_str(
+            r#"{"outputs": {
+                "a/x.js": {"imports": [], "inputs": {}},
+                "b/x.js": {"imports": [], "inputs": {}}
+            }}"#,
+        )
+        .unwrap()
+        .to_vite_manifest();
+
+        assert_eq!(
+            manifest.chunks.keys().collect::<Vec<_>>(),
+            vec!["_b/x.js", "_x.js"]
+        );
+    }
+
+    #[test]
+    fn test_mjs_outputs_are_modules() {
+        let manifest = RawEsbuildMetafile::from_str(
+            r#"{"outputs": {
+                "dist/main.mjs": {
+                    "imports": [{"path": "dist/chunk.mjs", "kind": "import-statement"}],
+                    "inputs": {"src/main.ts": {"bytesInOutput": 1}},
+                    "entryPoint": "src/main.ts"
+                },
+                "dist/chunk.mjs": {
+                    "imports": [],
+                    "inputs": {"src/shared.ts": {"bytesInOutput": 1}}
+                }
+            }}"#,
+        )
+        .unwrap()
+        .to_vite_manifest();
+        let main = manifest.chunk("src/main.ts").unwrap();
+
+        assert_eq!(main.imports, vec!["_chunk.mjs"]);
+        assert!(main.assets.is_empty());
+        assert_eq!(
+            manifest
+                .chunk("_chunk.mjs")
+                .map(|chunk| chunk.src.as_deref()),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn test_from_str_rejects_invalid_json() {
+        let error = ViteManifest::from_str("{").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Vite manifest could not be parsed at line 1, column 1"
+        );
+        assert!(matches!(error, Error::ViteManifestParse { .. }));
+        assert!(matches!(
+            ViteManifest::from_str(r#"{"a.js":{"file":1}}"#),
+            Err(Error::ViteManifestParse { .. })
+        ));
+    }
+}
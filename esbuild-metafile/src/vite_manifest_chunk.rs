@@ -0,0 +1,27 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A single entry of a Vite `manifest.json`. Lists other than `css` and `assets`
+/// refer to keys of the manifest, not to files.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ViteManifestChunk {
+    /// Relative to Vite's `outDir`. Manifests exported from an esbuild metafile keep the
+    /// output paths esbuild wrote, outdir included, so they resolve to the same paths as
+    /// the metafile lookups.
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub src: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_entry: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_dynamic_entry: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dynamic_imports: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub css: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<String>,
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "cli")]
+
+use std::process::Command;
+
+fn esbuild_metafile(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_esbuild-metafile"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_version_exits_with_success() {
+    let output = esbuild_metafile(&["--version"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        concat!("esbuild-metafile ", env!("CARGO_PKG_VERSION"), "\n")
+    );
+}
+
+#[test]
+fn test_usage_error_exits_with_error() {
+    let output = esbuild_metafile(&["validate"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--metafile <METAFILE>"));
+}